@@ -1,4 +1,6 @@
-use bitnuc::{as_2bit, decode, encode_alloc, from_2bit};
+use std::hint::black_box;
+
+use bitnuc::{as_2bit, decode, encode_alloc, from_2bit, hdist};
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 
 fn generate_sequence(length: usize) -> Vec<u8> {
@@ -100,11 +102,48 @@ fn bench_decoding(c: &mut Criterion) {
     group.finish();
 }
 
+/// Many short calls, where the cost of choosing a backend is not hidden by the work.
+///
+/// Compare against another revision with criterion baselines, e.g. run
+/// `cargo bench --bench simd_comparison -- dispatch --save-baseline before` on it and
+/// `cargo bench --bench simd_comparison -- dispatch --baseline before` on this one.
+fn bench_dispatch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dispatch");
+
+    // Packing every k-mer of a read
+    let seq = generate_sequence(1024);
+    for k in [11, 21, 31].iter() {
+        group.bench_with_input(BenchmarkId::new("kmers", k), k, |b, &k| {
+            b.iter(|| {
+                seq.windows(k)
+                    .map(|kmer| as_2bit(black_box(kmer)).unwrap())
+                    .fold(0u64, |acc, bits| acc ^ bits)
+            })
+        });
+    }
+
+    // Distances between short reads
+    let reads: Vec<Vec<u64>> = (0..256)
+        .map(|i| encode_alloc(&generate_sequence(150 + i % 4)[i % 4..]).unwrap())
+        .collect();
+    group.bench_function("hdist", |b| {
+        b.iter(|| {
+            reads
+                .iter()
+                .map(|read| hdist(&reads[0], black_box(read), 150).unwrap())
+                .sum::<u32>()
+        })
+    });
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_packing,
     bench_encoding,
     bench_unpacking,
-    bench_decoding
+    bench_decoding,
+    bench_dispatch
 );
 criterion_main!(benches);
@@ -0,0 +1,412 @@
+use crate::utils::functions::append_packed;
+use crate::{encode_append, from_2bit, hdist_scalar, NucleotideError, PackedSequence};
+
+/// Many nucleotide sequences packed end-to-end into one buffer.
+///
+/// Records are packed back to back with no padding between them, and an offsets table
+/// records where each one starts. Pushing a record only grows the shared buffers, so
+/// storing millions of short reads costs two allocations rather than one per read.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::PackedSeqArena;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut arena = PackedSeqArena::new();
+/// arena.push(b"ACGTACGTAC")?;
+/// arena.push(b"ACGTTCGTAA")?;
+/// arena.push(b"GATTACA")?;
+/// assert_eq!(arena.len(), 3);
+/// assert_eq!(arena.n_bases(), 27);
+///
+/// let mut dbuf = Vec::new();
+/// arena.decode(2, &mut dbuf)?;
+/// assert_eq!(dbuf, b"GATTACA");
+///
+/// assert_eq!(arena.hdist(0, 1)?, 2);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct PackedSeqArena {
+    /// The packed bases of every record
+    data: Vec<u64>,
+    /// The base offset of each record, followed by the total number of bases
+    offsets: Vec<usize>,
+}
+
+impl Default for PackedSeqArena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PackedSeqArena {
+    /// Creates an empty arena.
+    pub fn new() -> Self {
+        Self {
+            data: Vec::new(),
+            offsets: vec![0],
+        }
+    }
+
+    /// Creates an empty arena with room for `n_records` records holding `n_bases` bases
+    /// in total.
+    pub fn with_capacity(n_records: usize, n_bases: usize) -> Self {
+        let mut offsets = Vec::with_capacity(n_records + 1);
+        offsets.push(0);
+        Self {
+            data: Vec::with_capacity(n_bases.div_ceil(32)),
+            offsets,
+        }
+    }
+
+    /// Creates an arena from packed bases and record offsets that are already laid out.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn from_parts(data: Vec<u64>, offsets: Vec<usize>) -> Self {
+        debug_assert_eq!(data.len(), offsets[offsets.len() - 1].div_ceil(32));
+        Self { data, offsets }
+    }
+
+    /// Returns the number of records.
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Returns true if the arena holds no records.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the total number of bases across all records.
+    pub fn n_bases(&self) -> usize {
+        self.offsets[self.len()]
+    }
+
+    /// Removes every record, keeping the allocated capacity.
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.offsets.truncate(1);
+    }
+
+    /// Packs a sequence onto the end of the arena and returns its index.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NucleotideError::InvalidBase` if the sequence contains a base other than
+    /// A, C, G or T (case insensitive). The arena is left unchanged.
+    pub fn push(&mut self, seq: &[u8]) -> Result<usize, NucleotideError> {
+        let n_bases = self.n_bases();
+        encode_append(seq, &mut self.data, n_bases)?;
+        self.offsets.push(n_bases + seq.len());
+        Ok(self.len() - 1)
+    }
+
+    /// Appends every record of `other` to the end of the arena, preserving their order.
+    ///
+    /// The packed bases are shifted into place without being decoded.
+    pub fn extend_from_arena(&mut self, other: &PackedSeqArena) {
+        let n_bases = self.n_bases();
+        append_packed(&mut self.data, n_bases, &other.data, other.n_bases())
+            .expect("arena buffers hold all of their bases");
+        self.offsets
+            .extend(other.offsets[1..].iter().map(|offset| n_bases + offset));
+    }
+
+    /// Returns a borrowed view of the record at `index`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NucleotideError::IndexOutOfBounds` if there is no record at `index`.
+    pub fn get(&self, index: usize) -> Result<PackedSeqView<'_>, NucleotideError> {
+        if index >= self.len() {
+            return Err(NucleotideError::IndexOutOfBounds {
+                index,
+                length: self.len(),
+            });
+        }
+        Ok(self.view(self.offsets[index], self.offsets[index + 1]))
+    }
+
+    #[inline(always)]
+    fn view(&self, start: usize, end: usize) -> PackedSeqView<'_> {
+        PackedSeqView {
+            data: &self.data[start / 32..end.div_ceil(32)],
+            offset: start % 32,
+            length: end - start,
+        }
+    }
+
+    /// Returns an iterator over views of the records, in the order they were pushed.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = PackedSeqView<'_>> + '_ {
+        self.offsets
+            .windows(2)
+            .map(|bounds| self.view(bounds[0], bounds[1]))
+    }
+
+    /// Unpacks the record at `index`, appending its bases to `dbuf`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NucleotideError::IndexOutOfBounds` if there is no record at `index`.
+    pub fn decode(&self, index: usize, dbuf: &mut Vec<u8>) -> Result<(), NucleotideError> {
+        self.get(index)?.decode(dbuf)
+    }
+
+    /// Calculates the hamming distance between the records at `i` and `j`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NucleotideError::IndexOutOfBounds` if either record does not exist.
+    ///
+    /// Returns `NucleotideError::InvalidLength` if the records differ in length.
+    pub fn hdist(&self, i: usize, j: usize) -> Result<u32, NucleotideError> {
+        self.get(i)?.hdist(&self.get(j)?)
+    }
+}
+
+/// A borrowed record of a [`PackedSeqArena`].
+///
+/// The record's bases need not start on a word boundary; they are realigned on the fly
+/// without allocating.
+#[derive(Debug, Clone, Copy)]
+pub struct PackedSeqView<'a> {
+    /// The words overlapping the record
+    data: &'a [u64],
+    /// The position of the first base within `data[0]`
+    offset: usize,
+    /// The number of bases in the record
+    length: usize,
+}
+
+impl<'a> PackedSeqView<'a> {
+    /// Returns the number of bases in the record.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns true if the record contains no bases.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Returns the nucleotide at `index`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NucleotideError::IndexOutOfBounds` if the index is past the end of the record.
+    pub fn get(&self, index: usize) -> Result<u8, NucleotideError> {
+        if index >= self.length {
+            return Err(NucleotideError::IndexOutOfBounds {
+                index,
+                length: self.length,
+            });
+        }
+        let position = self.offset + index;
+        let bits = (self.data[position / 32] >> ((position % 32) * 2)) & 0b11;
+        Ok(b"ACGT"[bits as usize])
+    }
+
+    /// Returns the packed word holding bases `32 * index..32 * (index + 1)` of the record.
+    #[inline(always)]
+    fn word(&self, index: usize) -> u64 {
+        let shift = self.offset * 2;
+        let mut word = self.data[index] >> shift;
+        if shift > 0 {
+            if let Some(next) = self.data.get(index + 1) {
+                word |= next << (64 - shift);
+            }
+        }
+        match self.length - index * 32 {
+            rem @ 1..=31 => word & ((1u64 << (rem * 2)) - 1),
+            _ => word,
+        }
+    }
+
+    /// Returns an iterator over the record's packed words.
+    ///
+    /// The words are identical to those produced by `encode` for the record's bases.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bitnuc::{encode_alloc, PackedSeqArena};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut arena = PackedSeqArena::new();
+    /// arena.push(b"GATTACA")?;
+    /// arena.push(b"ACGTACGTACGTACGTACGTACGTACGTACGTTTGCA")?;
+    ///
+    /// let words: Vec<u64> = arena.get(1)?.words().collect();
+    /// assert_eq!(words, encode_alloc(b"ACGTACGTACGTACGTACGTACGTACGTACGTTTGCA")?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn words(&self) -> impl ExactSizeIterator<Item = u64> + 'a {
+        let view = *self;
+        (0..self.length.div_ceil(32)).map(move |index| view.word(index))
+    }
+
+    /// Unpacks the record, appending its bases to `dbuf`.
+    ///
+    /// # Errors
+    ///
+    /// If the record cannot be unpacked, an error is returned.
+    pub fn decode(&self, dbuf: &mut Vec<u8>) -> Result<(), NucleotideError> {
+        dbuf.reserve(self.length);
+        for (index, word) in self.words().enumerate() {
+            from_2bit(word, (self.length - index * 32).min(32), dbuf)?;
+        }
+        Ok(())
+    }
+
+    /// Unpacks the record into a newly allocated buffer.
+    ///
+    /// # Errors
+    ///
+    /// If the record cannot be unpacked, an error is returned.
+    pub fn to_vec(&self) -> Result<Vec<u8>, NucleotideError> {
+        let mut dbuf = Vec::with_capacity(self.length);
+        self.decode(&mut dbuf)?;
+        Ok(dbuf)
+    }
+
+    /// Calculates the hamming distance to another record of the same length.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NucleotideError::InvalidLength` if the records differ in length.
+    pub fn hdist(&self, other: &PackedSeqView<'_>) -> Result<u32, NucleotideError> {
+        if self.length != other.length {
+            return Err(NucleotideError::InvalidLength(other.length));
+        }
+        let mut total_dist = 0;
+        for (index, (u, v)) in self.words().zip(other.words()).enumerate() {
+            total_dist += hdist_scalar(u, v, (self.length - index * 32).min(32))?;
+        }
+        Ok(total_dist)
+    }
+
+    /// Copies the record into an owned [`PackedSequence`].
+    pub fn to_packed(&self) -> PackedSequence {
+        PackedSequence::from_parts(self.words().collect(), self.length, Vec::new(), Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{encode_alloc, hdist};
+    use nucgen::Sequence;
+
+    const LENGTHS: [usize; 12] = [0, 1, 7, 31, 32, 33, 50, 63, 64, 65, 100, 150];
+
+    fn random_arena() -> (PackedSeqArena, Vec<Vec<u8>>) {
+        let mut rng = rand::thread_rng();
+        let mut seq = Sequence::new();
+        let mut arena = PackedSeqArena::new();
+        let mut records = Vec::new();
+        for _ in 0..3 {
+            for len in LENGTHS {
+                seq.fill_buffer(&mut rng, len);
+                assert_eq!(arena.push(seq.bytes()).unwrap(), records.len());
+                records.push(seq.bytes().to_vec());
+            }
+        }
+        (arena, records)
+    }
+
+    #[test]
+    fn test_arena_roundtrip() {
+        let (arena, records) = random_arena();
+        assert_eq!(arena.len(), records.len());
+        assert_eq!(arena.n_bases(), records.iter().map(Vec::len).sum::<usize>());
+        assert_eq!(arena.data.len(), arena.n_bases().div_ceil(32));
+
+        let mut dbuf = Vec::new();
+        for (i, (view, record)) in arena.iter().zip(&records).enumerate() {
+            assert_eq!(view.len(), record.len());
+            assert_eq!(view.to_vec().unwrap(), *record);
+
+            dbuf.clear();
+            arena.decode(i, &mut dbuf).unwrap();
+            assert_eq!(dbuf, *record);
+
+            if !record.is_empty() {
+                let words: Vec<u64> = view.words().collect();
+                assert_eq!(words, encode_alloc(record).unwrap());
+                assert_eq!(view.to_packed(), PackedSequence::new(record).unwrap());
+                assert_eq!(
+                    view.get(record.len() - 1).unwrap(),
+                    record[record.len() - 1]
+                );
+            }
+            assert!(view.get(record.len()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_arena_hdist() {
+        let (arena, records) = random_arena();
+        for i in 0..records.len() {
+            for j in 0..records.len() {
+                let len = records[i].len();
+                if len != records[j].len() {
+                    assert_eq!(
+                        arena.hdist(i, j),
+                        Err(NucleotideError::InvalidLength(records[j].len()))
+                    );
+                } else if len > 0 {
+                    let u = encode_alloc(&records[i]).unwrap();
+                    let v = encode_alloc(&records[j]).unwrap();
+                    assert_eq!(arena.hdist(i, j), hdist(&u, &v, len));
+                } else {
+                    assert_eq!(arena.hdist(i, j), Ok(0));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_arena_extend() {
+        let (arena, records) = random_arena();
+        let mut merged = PackedSeqArena::new();
+        merged.push(b"GATTACA").unwrap();
+        merged.extend_from_arena(&arena);
+        merged.extend_from_arena(&PackedSeqArena::new());
+
+        let mut expected = PackedSeqArena::new();
+        expected.push(b"GATTACA").unwrap();
+        for record in &records {
+            expected.push(record).unwrap();
+        }
+        assert_eq!(merged, expected);
+    }
+
+    #[test]
+    fn test_arena_errors() {
+        let mut arena = PackedSeqArena::with_capacity(2, 64);
+        arena.push(b"ACGTA").unwrap();
+        assert_eq!(
+            arena.push(b"ACNGT"),
+            Err(NucleotideError::InvalidBase {
+                base: b'N',
+                position: 2
+            })
+        );
+        assert_eq!(arena.len(), 1);
+        assert_eq!(arena.n_bases(), 5);
+        assert_eq!(
+            arena.get(1).unwrap_err(),
+            NucleotideError::IndexOutOfBounds {
+                index: 1,
+                length: 1
+            }
+        );
+
+        arena.clear();
+        assert!(arena.is_empty());
+        assert_eq!(arena, PackedSeqArena::default());
+    }
+}
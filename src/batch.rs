@@ -0,0 +1,393 @@
+//! Parallel batch encoding, decoding and distance functions.
+//!
+//! Available with the `rayon` feature. Work is spread over the rayon thread pool, each
+//! thread reuses its own scratch buffers, and outputs are always in input order.
+//!
+//! ```rust
+//! use bitnuc::batch;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let reads = [&b"ACGTACGT"[..], b"ACGAACGT", b"TTGCAACG"];
+//!
+//! let arena = batch::encode_arena(&reads)?;
+//! assert_eq!(batch::decode_arena(&arena)?, reads);
+//!
+//! assert_eq!(batch::hdist_all(b"ACGTACGT", &reads)?, [0, 1, 6]);
+//! # Ok(())
+//! # }
+//! ```
+
+use rayon::prelude::*;
+
+use crate::{encode_append, hdist, NucleotideError, PackedSeqArena};
+
+/// The number of records handled per parallel task
+const BATCH_RECORDS: usize = 1024;
+
+/// Collects per-record results, returning the error of the earliest failing record.
+fn collect_ordered<T>(results: Vec<Result<T, NucleotideError>>) -> Result<Vec<T>, NucleotideError> {
+    results.into_iter().collect()
+}
+
+/// Collects per-record encoding results like [`collect_ordered`], shifting the position
+/// of an invalid base to its offset in the concatenation of `sequences`.
+fn collect_encoded<T, S: AsRef<[u8]>>(
+    results: Vec<Result<T, NucleotideError>>,
+    sequences: &[S],
+) -> Result<Vec<T>, NucleotideError> {
+    let mut items = Vec::with_capacity(results.len());
+    let mut offset = 0;
+    for (result, sequence) in results.into_iter().zip(sequences) {
+        items.push(result.map_err(|err| err.offset_position(offset))?);
+        offset += sequence.as_ref().len();
+    }
+    Ok(items)
+}
+
+/// Encodes a sequence into `ebuf`, leaving it empty for an empty sequence.
+#[inline(always)]
+fn encode_into(sequence: &[u8], ebuf: &mut Vec<u64>) -> Result<(), NucleotideError> {
+    if sequence.is_empty() {
+        ebuf.clear();
+        Ok(())
+    } else {
+        crate::encode(sequence, ebuf)
+    }
+}
+
+/// Encode many sequences in parallel, each into its own buffer.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidBase` if any sequence contains a base other than
+/// A, C, G or T (case insensitive). If several do, the error is that of the first one.
+/// Its position is the offset of the base in the concatenation of `sequences`, so that
+/// it identifies both the record and the base within it.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{batch, encode_alloc};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let ebufs = batch::encode(&[&b"ACGT"[..], b"GATTACA"])?;
+/// assert_eq!(ebufs, [encode_alloc(b"ACGT")?, encode_alloc(b"GATTACA")?]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn encode<S>(sequences: &[S]) -> Result<Vec<Vec<u64>>, NucleotideError>
+where
+    S: AsRef<[u8]> + Sync,
+{
+    let results = sequences
+        .par_iter()
+        .map(|sequence| {
+            let sequence = sequence.as_ref();
+            let mut ebuf = Vec::with_capacity(sequence.len().div_ceil(32));
+            encode_into(sequence, &mut ebuf)?;
+            Ok(ebuf)
+        })
+        .collect();
+    collect_encoded(results, sequences)
+}
+
+/// Encode many sequences in parallel into one [`PackedSeqArena`].
+///
+/// Record offsets are computed up front, so each batch of records is packed into a
+/// reused per-thread scratch buffer and copied straight to its final place in the arena.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidBase` if any sequence contains a base other than
+/// A, C, G or T (case insensitive). If several do, the error is that of the first one.
+/// Its position is the offset of the base in the arena, as for [`encode`].
+pub fn encode_arena<S>(sequences: &[S]) -> Result<PackedSeqArena, NucleotideError>
+where
+    S: AsRef<[u8]> + Sync,
+{
+    let mut offsets = Vec::with_capacity(sequences.len() + 1);
+    offsets.push(0);
+    for sequence in sequences {
+        offsets.push(offsets[offsets.len() - 1] + sequence.as_ref().len());
+    }
+    let n_bases = offsets[sequences.len()];
+    let mut data = vec![0u64; n_bases.div_ceil(32)];
+
+    // Each batch owns the words that start within its bases, so the batches write to
+    // disjoint slices. A word straddling two batches is owned by the earlier one.
+    let batch_starts: Vec<usize> = offsets.iter().step_by(BATCH_RECORDS).copied().collect();
+    let mut slices = Vec::with_capacity(batch_starts.len());
+    let mut rest = data.as_mut_slice();
+    for (i, &start) in batch_starts.iter().enumerate() {
+        let end = batch_starts.get(i + 1).copied().unwrap_or(n_bases);
+        let (owned, tail) = rest.split_at_mut(end.div_ceil(32) - start.div_ceil(32));
+        slices.push(owned);
+        rest = tail;
+    }
+
+    let results = sequences
+        .par_chunks(BATCH_RECORDS)
+        .zip(slices)
+        .enumerate()
+        .map_init(Vec::new, |scratch, (i, (batch, owned))| {
+            // Pack the batch at its bit offset within its first word
+            let start = offsets[i * BATCH_RECORDS];
+            let shift = start % 32;
+            scratch.clear();
+            if shift > 0 {
+                scratch.push(0);
+            }
+            let mut current_len = shift;
+            for sequence in batch {
+                let sequence = sequence.as_ref();
+                encode_append(sequence, scratch, current_len)
+                    .map_err(|err| err.offset_position(start - shift + current_len))?;
+                current_len += sequence.len();
+            }
+
+            // The leading partial word belongs to the previous batch
+            let (leading, words) = if shift > 0 {
+                (scratch[0], &scratch[1..])
+            } else {
+                (0, &scratch[..])
+            };
+            owned.copy_from_slice(words);
+            Ok(leading)
+        })
+        .collect();
+    let leading = collect_ordered(results)?;
+
+    for (&start, word) in batch_starts.iter().zip(leading) {
+        if start % 32 > 0 {
+            data[start / 32] |= word;
+        }
+    }
+    Ok(PackedSeqArena::from_parts(data, offsets))
+}
+
+/// Decode many packed sequences in parallel.
+///
+/// # Arguments
+///
+/// * `ebufs` - The buffers containing the packed nucleotides.
+/// * `lengths` - The number of nucleotides in each buffer.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidLength` if `ebufs` and `lengths` differ in length,
+/// or if a buffer holds fewer bases than its length.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::batch;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let reads = [&b"ACGT"[..], b"GATTACA"];
+/// let ebufs = batch::encode(&reads)?;
+/// assert_eq!(batch::decode(&ebufs, &[4, 7])?, reads);
+/// # Ok(())
+/// # }
+/// ```
+pub fn decode<E>(ebufs: &[E], lengths: &[usize]) -> Result<Vec<Vec<u8>>, NucleotideError>
+where
+    E: AsRef<[u64]> + Sync,
+{
+    if ebufs.len() != lengths.len() {
+        return Err(NucleotideError::InvalidLength(lengths.len()));
+    }
+    let results = ebufs
+        .par_iter()
+        .zip(lengths)
+        .map(|(ebuf, &n_bases)| {
+            let mut dbuf = Vec::with_capacity(n_bases);
+            if n_bases > 0 {
+                crate::decode(ebuf.as_ref(), n_bases, &mut dbuf)?;
+            }
+            Ok(dbuf)
+        })
+        .collect();
+    collect_ordered(results)
+}
+
+/// Decode every record of a [`PackedSeqArena`] in parallel.
+///
+/// # Errors
+///
+/// If a record cannot be unpacked, an error is returned.
+pub fn decode_arena(arena: &PackedSeqArena) -> Result<Vec<Vec<u8>>, NucleotideError> {
+    let results = (0..arena.len())
+        .into_par_iter()
+        .map(|index| arena.get(index)?.to_vec())
+        .collect();
+    collect_ordered(results)
+}
+
+/// Calculate the hamming distance from `query` to every target sequence in parallel.
+///
+/// The query is packed once, and each thread packs targets into a reused scratch buffer.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidBase` if the query or any target contains a base
+/// other than A, C, G or T (case insensitive). For the query its position is within the
+/// query, and for a target it is the offset of the base in the concatenation of
+/// `targets`, as for [`encode`].
+///
+/// Returns `NucleotideError::InvalidLength` if a target differs in length from the query.
+///
+/// If several targets fail, the error is that of the first one.
+pub fn hdist_all<S>(query: &[u8], targets: &[S]) -> Result<Vec<u32>, NucleotideError>
+where
+    S: AsRef<[u8]> + Sync,
+{
+    let mut packed_query = Vec::new();
+    encode_into(query, &mut packed_query)?;
+
+    let results = targets
+        .par_iter()
+        .map_init(Vec::new, |ebuf, target| {
+            let target = target.as_ref();
+            if target.len() != query.len() {
+                return Err(NucleotideError::InvalidLength(target.len()));
+            }
+            encode_into(target, ebuf)?;
+            hdist(&packed_query, ebuf, query.len())
+        })
+        .collect();
+    collect_encoded(results, targets)
+}
+
+/// Calculate the hamming distance from the record at `query` to every record of a
+/// [`PackedSeqArena`] in parallel.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::IndexOutOfBounds` if there is no record at `query`.
+///
+/// Returns `NucleotideError::InvalidLength` if a record differs in length from the query.
+/// If several do, the error is that of the first one.
+pub fn hdist_arena(arena: &PackedSeqArena, query: usize) -> Result<Vec<u32>, NucleotideError> {
+    let query = arena.get(query)?;
+    let results = (0..arena.len())
+        .into_par_iter()
+        .map(|index| query.hdist(&arena.get(index)?))
+        .collect();
+    collect_ordered(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encode_alloc;
+    use nucgen::Sequence;
+
+    fn random_reads(n_reads: usize, len: usize) -> Vec<Vec<u8>> {
+        let mut rng = rand::thread_rng();
+        let mut seq = Sequence::new();
+        (0..n_reads)
+            .map(|_| {
+                seq.fill_buffer(&mut rng, len);
+                seq.bytes().to_vec()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_batch_roundtrip() {
+        let mut reads = random_reads(3000, 150);
+        reads[17].clear();
+        reads[2500].truncate(37);
+
+        let ebufs = encode(&reads).unwrap();
+        for (ebuf, read) in ebufs.iter().zip(&reads) {
+            if !read.is_empty() {
+                assert_eq!(*ebuf, encode_alloc(read).unwrap());
+            }
+        }
+        let lengths: Vec<usize> = reads.iter().map(Vec::len).collect();
+        assert_eq!(decode(&ebufs, &lengths).unwrap(), reads);
+
+        let arena = encode_arena(&reads).unwrap();
+        let mut expected = PackedSeqArena::new();
+        for read in &reads {
+            expected.push(read).unwrap();
+        }
+        assert_eq!(arena, expected);
+        assert_eq!(decode_arena(&arena).unwrap(), reads);
+    }
+
+    #[test]
+    fn test_encode_arena_unaligned_batches() {
+        // Batches start part way through a word
+        let mut reads = random_reads(2 * BATCH_RECORDS + 5, 37);
+        for (i, read) in reads.iter_mut().enumerate() {
+            read.truncate(i % 41);
+        }
+        let mut expected = PackedSeqArena::new();
+        for read in &reads {
+            expected.push(read).unwrap();
+        }
+        assert_eq!(encode_arena(&reads).unwrap(), expected);
+        assert_eq!(encode_arena::<&[u8]>(&[]).unwrap(), PackedSeqArena::new());
+    }
+
+    #[test]
+    fn test_batch_hdist() {
+        let reads = random_reads(2000, 100);
+        let query = &reads[5];
+        let distances = hdist_all(query, &reads).unwrap();
+        let arena = encode_arena(&reads).unwrap();
+        assert_eq!(hdist_arena(&arena, 5).unwrap(), distances);
+
+        let packed_query = encode_alloc(query).unwrap();
+        for (read, distance) in reads.iter().zip(distances) {
+            let packed = encode_alloc(read).unwrap();
+            assert_eq!(hdist(&packed_query, &packed, 100).unwrap(), distance);
+        }
+        assert_eq!(hdist_all(b"", &[&b""[..], b""]).unwrap(), [0, 0]);
+    }
+
+    #[test]
+    fn test_batch_errors() {
+        let mut reads = random_reads(3000, 50);
+        reads[1500][7] = b'N';
+        reads[2900][3] = b'X';
+        // Positions are offsets into the concatenated reads
+        let expected = NucleotideError::InvalidBase {
+            base: b'N',
+            position: 1500 * 50 + 7,
+        };
+        assert_eq!(encode(&reads).unwrap_err(), expected);
+        assert_eq!(encode_arena(&reads).unwrap_err(), expected);
+        assert_eq!(hdist_all(&reads[0], &reads).unwrap_err(), expected);
+        assert_eq!(
+            hdist_all(&reads[2900], &reads[..10]).unwrap_err(),
+            NucleotideError::InvalidBase {
+                base: b'X',
+                position: 3
+            }
+        );
+
+        // Batches start part way through a word
+        let mut reads = random_reads(2 * BATCH_RECORDS, 37);
+        reads[0].truncate(5);
+        reads[BATCH_RECORDS + 1][0] = b'N';
+        assert_eq!(
+            encode_arena(&reads).unwrap_err(),
+            NucleotideError::InvalidBase {
+                base: b'N',
+                position: (BATCH_RECORDS + 1) * 37 - 32
+            }
+        );
+
+        assert_eq!(
+            hdist_all(b"ACGT", &[&b"ACGT"[..], b"ACG"]),
+            Err(NucleotideError::InvalidLength(3))
+        );
+        assert_eq!(
+            decode(&[vec![0u64]], &[4, 4]),
+            Err(NucleotideError::InvalidLength(2))
+        );
+    }
+}
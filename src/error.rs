@@ -1,8 +1,13 @@
-use std::fmt;
+use std::{fmt, io};
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug)]
+#[non_exhaustive]
 pub enum NucleotideError {
-    InvalidBase(u8),
+    /// A byte that is not a valid base, and its offset within the input
+    InvalidBase {
+        base: u8,
+        position: usize,
+    },
     SequenceTooLong(usize),
     InvalidLength(usize),
     IndexOutOfBounds {
@@ -15,12 +20,79 @@ pub enum NucleotideError {
         length: usize,
     },
     Unsupported,
+    /// A file that does not follow its format specification, and what is wrong with it
+    InvalidFormat(String),
+    /// A sequence name that is not present in a file
+    SequenceNotFound(String),
+    /// An error from an underlying reader or writer
+    Io(io::Error),
+}
+
+impl NucleotideError {
+    /// Shifts the position of an `InvalidBase` error by `offset`, for errors raised on a
+    /// chunk starting `offset` bytes into the input.
+    #[inline]
+    pub(crate) fn offset_position(self, offset: usize) -> Self {
+        match self {
+            NucleotideError::InvalidBase { base, position } => NucleotideError::InvalidBase {
+                base,
+                position: position + offset,
+            },
+            err => err,
+        }
+    }
+}
+
+impl PartialEq for NucleotideError {
+    /// I/O errors are equal if their kinds are equal.
+    fn eq(&self, other: &Self) -> bool {
+        use NucleotideError::*;
+        match (self, other) {
+            (
+                InvalidBase { base, position },
+                InvalidBase {
+                    base: other_base,
+                    position: other_position,
+                },
+            ) => base == other_base && position == other_position,
+            (SequenceTooLong(a), SequenceTooLong(b)) => a == b,
+            (InvalidLength(a), InvalidLength(b)) => a == b,
+            (
+                IndexOutOfBounds { index, length },
+                IndexOutOfBounds {
+                    index: other_index,
+                    length: other_length,
+                },
+            ) => index == other_index && length == other_length,
+            (
+                InvalidRange { start, end, length },
+                InvalidRange {
+                    start: other_start,
+                    end: other_end,
+                    length: other_length,
+                },
+            ) => start == other_start && end == other_end && length == other_length,
+            (Unsupported, Unsupported) => true,
+            (InvalidFormat(a), InvalidFormat(b)) => a == b,
+            (SequenceNotFound(a), SequenceNotFound(b)) => a == b,
+            (Io(a), Io(b)) => a.kind() == b.kind(),
+            _ => false,
+        }
+    }
 }
 
+impl Eq for NucleotideError {}
+
 impl fmt::Display for NucleotideError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            NucleotideError::InvalidBase(b) => write!(f, "Invalid nucleotide base: {}", b),
+            NucleotideError::InvalidBase { base, position } => {
+                write!(
+                    f,
+                    "Invalid nucleotide base: {} at position {}",
+                    base, position
+                )
+            }
             NucleotideError::SequenceTooLong(len) => {
                 write!(f, "Sequence length {} exceeds maximum", len)
             }
@@ -40,8 +112,74 @@ impl fmt::Display for NucleotideError {
                 )
             }
             NucleotideError::Unsupported => write!(f, "Unsupported architecture"),
+            NucleotideError::InvalidFormat(reason) => write!(f, "Invalid file format: {}", reason),
+            NucleotideError::SequenceNotFound(name) => {
+                write!(f, "No sequence named {:?}", name)
+            }
+            NucleotideError::Io(err) => write!(f, "I/O error: {}", err),
         }
     }
 }
 
-impl std::error::Error for NucleotideError {}
+impl std::error::Error for NucleotideError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NucleotideError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for NucleotideError {
+    fn from(err: io::Error) -> Self {
+        NucleotideError::Io(err)
+    }
+}
+
+impl From<NucleotideError> for io::Error {
+    /// Unwraps I/O errors and reports every other error as `InvalidData`.
+    fn from(err: NucleotideError) -> Self {
+        match err {
+            NucleotideError::Io(err) => err,
+            err => io::Error::new(io::ErrorKind::InvalidData, err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offset_position() {
+        let err = NucleotideError::InvalidBase {
+            base: b'N',
+            position: 3,
+        };
+        assert_eq!(
+            err.offset_position(32),
+            NucleotideError::InvalidBase {
+                base: b'N',
+                position: 35
+            }
+        );
+        assert_eq!(
+            NucleotideError::InvalidLength(3).offset_position(32),
+            NucleotideError::InvalidLength(3)
+        );
+    }
+
+    #[test]
+    fn test_io_conversions() {
+        let err = NucleotideError::from(io::Error::from(io::ErrorKind::UnexpectedEof));
+        assert_eq!(
+            err,
+            NucleotideError::Io(io::ErrorKind::UnexpectedEof.into())
+        );
+        assert!(std::error::Error::source(&err).is_some());
+        assert_eq!(io::Error::from(err).kind(), io::ErrorKind::UnexpectedEof);
+
+        let err = io::Error::from(NucleotideError::InvalidLength(3));
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
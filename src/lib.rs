@@ -144,7 +144,7 @@
 //!
 //! // Invalid nucleotide
 //! let err = as_2bit(b"ACGN").unwrap_err();
-//! assert!(matches!(err, NucleotideError::InvalidBase(b'N')));
+//! assert!(matches!(err, NucleotideError::InvalidBase { base: b'N', position: 3 }));
 //!
 //! // Sequence too long
 //! let long_seq = vec![b'A'; 33];
@@ -155,10 +155,13 @@
 //! ## Performance Considerations
 //!
 //! When working with many short sequences (like k-mers), using `as_2bit` and `from_2bit`
-//! directly can be more efficient than creating [`PackedSequence`] instances:
+//! directly can be more efficient than creating [`PackedSequence`] instances.
+//!
+//! For k-mers of a longer sequence, [`KmerIter`] shifts one base at a time into the
+//! previous k-mer instead of re-encoding every window:
 //!
 //! ```rust
-//! use bitnuc::{as_2bit, from_2bit};
+//! use bitnuc::{as_2bit, KmerIter, KmerPolicy};
 //! use std::collections::HashMap;
 //!
 //! fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -167,8 +170,7 @@
 //!
 //!     // Pack k-mers directly into u64s
 //!     let sequence = b"ACGTACGT";
-//!     for window in sequence.windows(4) {
-//!         let packed = as_2bit(window)?;
+//!     for (_pos, packed) in KmerIter::new(sequence, 4, KmerPolicy::Error)? {
 //!         *kmer_counts.entry(packed).or_insert(0) += 1;
 //!     }
 //!
@@ -207,17 +209,36 @@
 //! See the documentation for [`as_2bit`] and [`from_2bit`] for more details on
 //! working with packed sequences directly.
 
+mod arena;
+#[cfg(feature = "rayon")]
+pub mod batch;
 mod error;
 mod sequence;
+pub mod twobit;
 mod utils;
+mod writer;
 
+pub use arena::{PackedSeqArena, PackedSeqView};
 pub use error::NucleotideError;
 pub use sequence::PackedSequence;
+pub use utils::iupac;
+pub use utils::scheme::{self, EncodingScheme};
+pub use utils::translate::{self, Frame, GeneticCode};
+pub use utils::word::{self, PackedWord};
 pub use utils::{
     analysis::{BaseCount, GCContent},
-    as_2bit, decode, encode, encode_alloc, from_2bit, from_2bit_alloc, hdist, hdist_scalar,
-    split_packed,
+    as_2bit, as_2bit_msb, as_2bit_rna, as_2bit_u128, as_2bit_with_policy, bytes_as_words,
+    canonical_2bit, canonical_2bit_u128, concat_packed, decode, decode_bytes, decode_msb,
+    decode_rna, decode_with_n, decode_with_soft_mask, encode, encode_alloc, encode_append,
+    encode_bytes, encode_msb, encode_rna, encode_with_n, encode_with_policy, encode_with_soft_mask,
+    from_2bit, from_2bit_alloc, from_2bit_msb, from_2bit_rna, from_2bit_u128, hdist, hdist_scalar,
+    hdist_scalar_u128, is_valid_dna, is_valid_dna_uppercase, is_valid_iupac, is_valid_rna,
+    lsb_to_msb, msb_to_lsb, revcomp_2bit, revcomp_2bit_u128, revcomp_packed, split_packed,
+    words_as_bytes, Alphabet, CanonicalKmerIter, EncodeReport, InvalidBasePolicy, KmerItem,
+    KmerIter, KmerIterator, KmerOrder, KmerPolicy, MinimizerIter, MixHash, PackedKmerIter,
+    PackedValue, Strand, SyncmerIter, SyncmerKind,
 };
+pub use writer::PackedWriter;
 
 #[cfg(test)]
 mod testing {
@@ -1,11 +1,24 @@
-use crate::encode;
 use crate::error::NucleotideError;
+use crate::utils::mask::{
+    clear_runs, clip_runs, find_runs, is_soft_masked, reverse_runs, runs_contain,
+};
+use crate::utils::translate::translate_with_n;
+use crate::{
+    decode, decode_rna, encode, encode_rna, encode_with_n, revcomp_packed, Alphabet, PackedKmerIter,
+};
+use crate::{Frame, GeneticCode};
 use std::ops::Range;
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub struct PackedSequence {
     data: Vec<u64>,
     length: usize,
+    /// Sorted, non-overlapping runs of `N` bases, stored as `A` in `data`
+    n_runs: Vec<Range<usize>>,
+    /// Sorted, non-overlapping runs of soft-masked (lowercase) bases
+    soft_mask: Vec<Range<usize>>,
+    /// Whether `0b11` is read back as `T` or `U`
+    alphabet: Alphabet,
 }
 
 impl PackedSequence {
@@ -48,9 +61,170 @@ impl PackedSequence {
         Ok(Self {
             data,
             length: seq.len(),
+            n_runs: Vec::new(),
+            soft_mask: Vec::new(),
+            alphabet: Alphabet::Dna,
         })
     }
 
+    /// Creates a new `PackedSequence` from a byte slice that may contain `N` bases.
+    ///
+    /// The `N` bases (case insensitive) are recorded as runs alongside the 2-bit data
+    /// and are restored by [`get`](Self::get), [`slice`](Self::slice) and [`to_vec`](Self::to_vec).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bitnuc::PackedSequence;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let seq = PackedSequence::with_n(b"ACNNGT")?;
+    /// assert_eq!(seq.len(), 6);
+    /// assert_eq!(seq.n_runs(), &[2..4]);
+    /// assert_eq!(seq.get(2)?, b'N');
+    /// assert_eq!(seq.to_vec()?, b"ACNNGT");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `NucleotideError::InvalidBase` if the input contains characters other than
+    /// A, C, G, T or N.
+    pub fn with_n(seq: &[u8]) -> Result<Self, NucleotideError> {
+        let mut data = Vec::new();
+        let mut n_runs = Vec::new();
+        if !seq.is_empty() {
+            encode_with_n(seq, &mut data, &mut n_runs)?;
+        }
+
+        Ok(Self {
+            data,
+            length: seq.len(),
+            n_runs,
+            soft_mask: Vec::new(),
+            alphabet: Alphabet::Dna,
+        })
+    }
+
+    /// Creates a new `PackedSequence` that keeps the case of its input.
+    ///
+    /// Runs of lowercase bases, such as the repeat-masked regions of a reference,
+    /// are recorded alongside the 2-bit data and restored by [`get`](Self::get),
+    /// [`slice`](Self::slice) and [`to_vec`](Self::to_vec).
+    ///
+    /// `N` bases are accepted and recorded as in [`with_n`](Self::with_n).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bitnuc::PackedSequence;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let seq = PackedSequence::with_soft_mask(b"ACgtnNGT")?;
+    /// assert_eq!(seq.soft_mask(), &[2..5]);
+    /// assert_eq!(seq.n_runs(), &[4..6]);
+    /// assert_eq!(seq.to_vec()?, b"ACgtnNGT");
+    /// assert_eq!(seq.slice(1..4)?, b"Cgt");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `NucleotideError::InvalidBase` if the input contains characters other than
+    /// A, C, G, T or N (case insensitive).
+    pub fn with_soft_mask(seq: &[u8]) -> Result<Self, NucleotideError> {
+        let mut packed = Self::with_n(seq)?;
+        find_runs(seq, is_soft_masked, &mut packed.soft_mask);
+        Ok(packed)
+    }
+
+    /// Creates a new `PackedSequence` from a byte slice containing an RNA sequence.
+    ///
+    /// `U` is packed as `T` and the sequence is marked as [`Alphabet::Rna`], so
+    /// [`get`](Self::get), [`slice`](Self::slice) and [`to_vec`](Self::to_vec) emit `U`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bitnuc::{Alphabet, PackedSequence};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let seq = PackedSequence::new_rna(b"ACGUUA")?;
+    /// assert_eq!(seq.alphabet(), Alphabet::Rna);
+    /// assert_eq!(seq.get(3)?, b'U');
+    /// assert_eq!(seq.to_vec()?, b"ACGUUA");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `NucleotideError::InvalidBase` if the input contains characters other than
+    /// A, C, G, U or T (case insensitive).
+    pub fn new_rna(seq: &[u8]) -> Result<Self, NucleotideError> {
+        let mut data = Vec::new();
+        encode_rna(seq, &mut data)?;
+
+        Ok(Self {
+            data,
+            length: seq.len(),
+            n_runs: Vec::new(),
+            soft_mask: Vec::new(),
+            alphabet: Alphabet::Rna,
+        })
+    }
+
+    /// Returns the alphabet the sequence is decoded in.
+    pub fn alphabet(&self) -> Alphabet {
+        self.alphabet
+    }
+
+    /// Returns the same sequence decoded in another alphabet.
+    ///
+    /// The packed data is shared by both alphabets, so this only changes whether
+    /// `0b11` is read back as `T` or `U`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bitnuc::{Alphabet, PackedSequence};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let seq = PackedSequence::new(b"ATTG")?.into_alphabet(Alphabet::Rna);
+    /// assert_eq!(seq.to_vec()?, b"AUUG");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_alphabet(self, alphabet: Alphabet) -> Self {
+        Self { alphabet, ..self }
+    }
+
+    /// Wraps packed words produced by `encode` for a sequence of `length` bases.
+    ///
+    /// The `N` bases in `n_runs` must already be stored as `A`.
+    pub(crate) fn from_parts(
+        data: Vec<u64>,
+        length: usize,
+        n_runs: Vec<Range<usize>>,
+        soft_mask: Vec<Range<usize>>,
+    ) -> Self {
+        debug_assert_eq!(data.len(), length.div_ceil(32));
+        Self {
+            data,
+            length,
+            n_runs,
+            soft_mask,
+            alphabet: Alphabet::Dna,
+        }
+    }
+
+    /// Returns the packed words of the sequence.
+    pub(crate) fn words(&self) -> &[u64] {
+        &self.data
+    }
+
     /// Returns the number of bases in the sequence.
     ///
     /// # Examples
@@ -85,6 +259,61 @@ impl PackedSequence {
         self.length == 0
     }
 
+    /// Returns the runs of `N` bases in the sequence as sorted, half-open ranges.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bitnuc::PackedSequence;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let seq = PackedSequence::with_n(b"NACGTNN")?;
+    /// assert_eq!(seq.n_runs(), &[0..1, 5..7]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn n_runs(&self) -> &[Range<usize>] {
+        &self.n_runs
+    }
+
+    /// Returns the number of `N` bases in the sequence.
+    pub fn n_count(&self) -> usize {
+        self.n_runs.iter().map(|run| run.len()).sum()
+    }
+
+    /// Returns true if the base at the given position is an `N`.
+    ///
+    /// Positions past the end of the sequence are never `N`.
+    pub fn is_n(&self, index: usize) -> bool {
+        runs_contain(&self.n_runs, index)
+    }
+
+    /// Returns the soft-masked (lowercase) ranges of the sequence as sorted, half-open ranges.
+    ///
+    /// Only sequences created with [`with_soft_mask`](Self::with_soft_mask) carry a mask.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bitnuc::PackedSequence;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let seq = PackedSequence::with_soft_mask(b"acGTAcgt")?;
+    /// assert_eq!(seq.soft_mask(), &[0..2, 5..8]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn soft_mask(&self) -> &[Range<usize>] {
+        &self.soft_mask
+    }
+
+    /// Returns true if the base at the given position is soft-masked.
+    ///
+    /// Positions past the end of the sequence are never soft-masked.
+    pub fn is_soft_masked(&self, index: usize) -> bool {
+        runs_contain(&self.soft_mask, index)
+    }
+
     /// Returns the nucleotide at the given position.
     ///
     /// # Examples
@@ -125,13 +354,19 @@ impl PackedSequence {
         let bit_idx = (index % 32) * 2;
         let bits = (self.data[chunk_idx] >> bit_idx) & 0b11;
 
-        Ok(match bits {
+        let base = match bits {
+            _ if self.is_n(index) => b'N',
             0b00 => b'A',
             0b01 => b'C',
             0b10 => b'G',
-            0b11 => b'T',
+            0b11 => self.alphabet.thymine(),
             _ => unreachable!(),
-        })
+        };
+        if self.is_soft_masked(index) {
+            Ok(base.to_ascii_lowercase())
+        } else {
+            Ok(base)
+        }
     }
 
     /// Returns a subsequence within the given range.
@@ -204,9 +439,17 @@ impl PackedSequence {
             });
         }
 
+        let bases = self.alphabet.bases();
         let mut result = Vec::with_capacity(range.end - range.start);
-        for i in range {
-            result.push(self.get(i)?);
+        for i in range.clone() {
+            let bits = (self.data[i / 32] >> ((i % 32) * 2)) & 0b11;
+            result.push(bases[bits as usize]);
+        }
+        for run in clip_runs(&self.n_runs, range.clone()) {
+            result[run].fill(b'N');
+        }
+        for run in clip_runs(&self.soft_mask, range) {
+            result[run].make_ascii_lowercase();
         }
         Ok(result)
     }
@@ -258,13 +501,126 @@ impl PackedSequence {
     /// For frequent access to subsequences, consider using `slice()` or individual
     /// base access via `get()` instead.
     pub fn to_vec(&self) -> Result<Vec<u8>, NucleotideError> {
-        self.slice(0..self.length)
+        let mut result = Vec::with_capacity(self.length);
+        match self.alphabet {
+            _ if self.length == 0 => {}
+            Alphabet::Dna => decode(&self.data, self.length, &mut result)?,
+            Alphabet::Rna => decode_rna(&self.data, self.length, &mut result)?,
+        }
+        for run in &self.n_runs {
+            result[run.clone()].fill(b'N');
+        }
+        for run in &self.soft_mask {
+            result[run.clone()].make_ascii_lowercase();
+        }
+        Ok(result)
+    }
+
+    /// Returns the reverse complement of the sequence.
+    ///
+    /// The reverse complement is computed directly on the packed representation
+    /// without decoding.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bitnuc::PackedSequence;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let seq = PackedSequence::new(b"AACGTG")?;
+    /// let rc = seq.reverse_complement()?;
+    /// assert_eq!(rc.to_vec()?, b"CACGTT");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reverse_complement(&self) -> Result<Self, NucleotideError> {
+        let mut data = Vec::with_capacity(self.data.len());
+        revcomp_packed(&self.data, self.length, &mut data)?;
+        let n_runs = reverse_runs(&self.n_runs, self.length);
+        clear_runs(&mut data, &n_runs);
+        Ok(Self {
+            data,
+            length: self.length,
+            n_runs,
+            soft_mask: reverse_runs(&self.soft_mask, self.length),
+            alphabet: self.alphabet,
+        })
+    }
+
+    /// Returns an iterator over the k-mers of the sequence.
+    ///
+    /// The k-mers are read directly from the packed representation and yielded
+    /// as `(position, kmer)` pairs, packed in the same way as `as_2bit`.
+    ///
+    /// Any `N` bases are read as `A`; use [`n_runs`](Self::n_runs) to discard the
+    /// k-mers overlapping them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bitnuc::{as_2bit, PackedSequence};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let seq = PackedSequence::new(b"ACGTA")?;
+    /// let kmers: Vec<_> = seq.kmers(4)?.collect();
+    /// assert_eq!(kmers, vec![(0, as_2bit(b"ACGT")?), (1, as_2bit(b"CGTA")?)]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `NucleotideError::InvalidLength` if `k` is zero or greater than 32.
+    pub fn kmers(&self, k: usize) -> Result<PackedKmerIter<'_>, NucleotideError> {
+        PackedKmerIter::new(&self.data, self.length, k)
+    }
+
+    /// Translates one reading frame of the sequence into amino acids.
+    ///
+    /// Stop codons are written as `*`, and codons overlapping an `N` as `X`. If
+    /// `to_stop` is set, the translation ends before the first stop codon.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bitnuc::{Frame, GeneticCode, PackedSequence};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let seq = PackedSequence::with_n(b"ATGNCCTGAAAA")?;
+    /// assert_eq!(seq.translate(Frame::Forward0, &GeneticCode::STANDARD, false)?, b"MX*K");
+    /// assert_eq!(seq.translate(Frame::Forward0, &GeneticCode::STANDARD, true)?, b"MX");
+    /// assert_eq!(seq.translate(Frame::Reverse0, &GeneticCode::STANDARD, false)?, b"FSXH");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns any error returned by [`translate`](crate::translate::translate).
+    pub fn translate(
+        &self,
+        frame: Frame,
+        code: &GeneticCode,
+        to_stop: bool,
+    ) -> Result<Vec<u8>, NucleotideError> {
+        let mut result = Vec::new();
+        translate_with_n(
+            &self.data,
+            self.length,
+            &self.n_runs,
+            frame,
+            code,
+            to_stop,
+            &mut result,
+        )?;
+        Ok(result)
     }
 }
 #[cfg(test)]
 mod tests {
     use crate::error::NucleotideError;
     use crate::sequence::PackedSequence;
+    use crate::Alphabet;
     use std::collections::HashSet;
 
     #[test]
@@ -315,6 +671,127 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_sequence_reverse_complement() {
+        let seq = PackedSequence::new(b"ACGTTGCAACGTTGCAACGTTGCAACGTTGCAACGGA").unwrap();
+        let rc = seq.reverse_complement().unwrap();
+        assert_eq!(rc.len(), seq.len());
+        assert_eq!(
+            rc.to_vec().unwrap(),
+            b"TCCGTTGCAACGTTGCAACGTTGCAACGTTGCAACGT"
+        );
+        assert_eq!(rc.reverse_complement().unwrap(), seq);
+
+        let empty = PackedSequence::new(b"").unwrap();
+        assert_eq!(empty.reverse_complement().unwrap(), empty);
+    }
+
+    #[test]
+    fn test_sequence_kmers() {
+        let seq = PackedSequence::new(b"ACGTACGTACGTACGTACGTACGTACGTACGTTTGCA").unwrap();
+        let kmers: Vec<_> = seq.kmers(8).unwrap().map(|(_, kmer)| kmer).collect();
+        let expected: Vec<_> = seq
+            .to_vec()
+            .unwrap()
+            .windows(8)
+            .map(|window| crate::as_2bit(window).unwrap())
+            .collect();
+        assert_eq!(kmers, expected);
+
+        let empty = PackedSequence::new(b"").unwrap();
+        assert_eq!(empty.kmers(3).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_sequence_with_n() {
+        let raw = b"NNACGTnNACGTACGTACGTACGTACGTACGTACGTACGTNN";
+        let seq = PackedSequence::with_n(raw).unwrap();
+        assert_eq!(seq.len(), raw.len());
+        assert_eq!(seq.n_runs(), &[0..2, 6..8, 40..42]);
+        assert_eq!(seq.n_count(), 6);
+        assert_eq!(seq.get(1).unwrap(), b'N');
+        assert_eq!(seq.get(2).unwrap(), b'A');
+        assert_eq!(seq.slice(5..9).unwrap(), b"TNNA");
+        assert_eq!(seq.slice(39..42).unwrap(), b"TNN");
+        assert_eq!(seq.to_vec().unwrap(), raw.to_ascii_uppercase());
+
+        // Sequences without N are identical to those from `new`
+        assert_eq!(
+            PackedSequence::with_n(b"ACGT").unwrap(),
+            PackedSequence::new(b"ACGT").unwrap()
+        );
+        assert!(PackedSequence::with_n(b"ACRT").is_err());
+    }
+
+    #[test]
+    fn test_sequence_with_n_reverse_complement() {
+        let seq = PackedSequence::with_n(b"NNACGTTNCA").unwrap();
+        let rc = seq.reverse_complement().unwrap();
+        assert_eq!(rc.to_vec().unwrap(), b"TGNAACGTNN");
+        assert_eq!(rc, PackedSequence::with_n(b"TGNAACGTNN").unwrap());
+        assert_eq!(rc.reverse_complement().unwrap(), seq);
+    }
+
+    #[test]
+    fn test_sequence_with_soft_mask() {
+        let raw = b"ACGTacgtACGTACGTACGTACGTACGTACGTACgtnnNNacgTT";
+        let seq = PackedSequence::with_soft_mask(raw).unwrap();
+        assert_eq!(seq.soft_mask(), &[4..8, 34..38, 40..43]);
+        assert_eq!(seq.n_runs().len(), 1);
+        assert_eq!(seq.n_runs()[0], 36..40);
+        assert!(seq.is_soft_masked(4));
+        assert!(!seq.is_soft_masked(8));
+        assert_eq!(seq.get(5).unwrap(), b'c');
+        assert_eq!(seq.get(36).unwrap(), b'n');
+        assert_eq!(seq.get(38).unwrap(), b'N');
+        assert_eq!(seq.slice(6..10).unwrap(), b"gtAC");
+        assert_eq!(seq.slice(33..41).unwrap(), b"CgtnnNNa");
+        assert_eq!(seq.to_vec().unwrap(), raw);
+
+        // `new` rejects N, and drops the mask of ACGT-only input
+        assert_eq!(
+            PackedSequence::new(raw).unwrap_err(),
+            NucleotideError::InvalidBase {
+                base: b'n',
+                position: 36
+            }
+        );
+        assert!(PackedSequence::new(b"acgt").unwrap().soft_mask().is_empty());
+    }
+
+    #[test]
+    fn test_sequence_with_soft_mask_reverse_complement() {
+        let seq = PackedSequence::with_soft_mask(b"aaCGTnNt").unwrap();
+        let rc = seq.reverse_complement().unwrap();
+        assert_eq!(rc.to_vec().unwrap(), b"aNnACGtt");
+        assert_eq!(rc, PackedSequence::with_soft_mask(b"aNnACGtt").unwrap());
+    }
+
+    #[test]
+    fn test_sequence_rna() {
+        let raw = b"ACGUUGCAACGUUGCAACGUUGCAACGUUGCAACGGAu";
+        let seq = PackedSequence::new_rna(raw).unwrap();
+        assert_eq!(seq.alphabet(), Alphabet::Rna);
+        assert_eq!(seq.get(3).unwrap(), b'U');
+        assert_eq!(seq.slice(2..6).unwrap(), b"GUUG");
+        assert_eq!(seq.to_vec().unwrap(), raw.to_ascii_uppercase());
+
+        let rc = seq.reverse_complement().unwrap();
+        assert_eq!(rc.alphabet(), Alphabet::Rna);
+        assert_eq!(
+            rc.to_vec().unwrap(),
+            b"AUCCGUUGCAACGUUGCAACGUUGCAACGUUGCAACGU"
+        );
+
+        // DNA and RNA share the packed data but are distinct sequences
+        let dna = PackedSequence::new(b"ACGT").unwrap();
+        let rna = PackedSequence::new_rna(b"ACGU").unwrap();
+        assert_ne!(dna, rna);
+        assert_eq!(dna.into_alphabet(Alphabet::Rna), rna);
+        assert!(PackedSequence::new(b"ACGU").is_err());
+        assert!(PackedSequence::new_rna(b"").unwrap().is_empty());
+    }
+
     #[test]
     fn test_sequence_equality() {
         let seq1 = PackedSequence::new(b"ACGT").unwrap();
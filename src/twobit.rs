@@ -0,0 +1,606 @@
+//! Reading and writing UCSC `.2bit` files.
+//!
+//! A `.2bit` file stores each sequence 4 bases per byte in T/C/A/G order (T=00, C=01,
+//! A=10, G=11) with the first base in the high bits of each byte, alongside runs of
+//! `N` bases and soft-masked (lowercase) bases. The reader converts the packed bytes
+//! straight into the crate's layout, so regions load into a [`PackedSequence`] without
+//! an ASCII round trip, and the runs become its [`n_runs`](PackedSequence::n_runs) and
+//! [`soft_mask`](PackedSequence::soft_mask).
+//!
+//! ```rust
+//! use bitnuc::twobit::{write_twobit, TwoBitReader};
+//! use bitnuc::PackedSequence;
+//! use std::io::Cursor;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let chr1 = PackedSequence::with_soft_mask(b"ACGTNNacgtACGT")?;
+//! let chr2 = PackedSequence::new(b"GATTACA")?;
+//!
+//! let mut file = Vec::new();
+//! write_twobit(&mut file, [("chr1", &chr1), ("chr2", &chr2)])?;
+//!
+//! let mut reader = TwoBitReader::new(Cursor::new(file))?;
+//! assert_eq!(reader.names().collect::<Vec<_>>(), ["chr1", "chr2"]);
+//! assert_eq!(reader.read_sequence("chr1")?, chr1);
+//! assert_eq!(reader.read_region_ascii("chr1", 3..9)?, b"TNNacg");
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::path::Path;
+
+use crate::utils::mask::{clear_runs, clip_runs};
+use crate::{NucleotideError, PackedSequence};
+
+/// The file signature, which also determines the byte order of the file
+const SIGNATURE: u32 = 0x1A41_2743;
+
+/// Builds a table mapping `.2bit` bytes to the crate's byte layout.
+const fn from_twobit_table() -> [u8; 256] {
+    // T, C, A, G to A=00, C=01, G=10, T=11
+    let codes = [0b11, 0b01, 0b00, 0b10];
+    let mut table = [0u8; 256];
+    let mut byte = 0;
+    while byte < 256 {
+        let mut out = 0u8;
+        let mut i = 0;
+        while i < 4 {
+            out |= codes[(byte >> (6 - 2 * i)) & 0b11] << (2 * i);
+            i += 1;
+        }
+        table[byte] = out;
+        byte += 1;
+    }
+    table
+}
+
+/// Builds a table mapping bytes in the crate's layout to `.2bit` bytes.
+const fn to_twobit_table() -> [u8; 256] {
+    // A, C, G, T to T=00, C=01, A=10, G=11
+    let codes = [0b10, 0b01, 0b11, 0b00];
+    let mut table = [0u8; 256];
+    let mut byte = 0;
+    while byte < 256 {
+        let mut out = 0u8;
+        let mut i = 0;
+        while i < 4 {
+            out |= codes[(byte >> (2 * i)) & 0b11] << (6 - 2 * i);
+            i += 1;
+        }
+        table[byte] = out;
+        byte += 1;
+    }
+    table
+}
+
+const FROM_TWOBIT: [u8; 256] = from_twobit_table();
+const TO_TWOBIT: [u8; 256] = to_twobit_table();
+
+fn invalid_format(reason: &str) -> NucleotideError {
+    NucleotideError::InvalidFormat(reason.to_string())
+}
+
+/// The header of a sequence record
+#[derive(Debug, Clone)]
+struct Record {
+    length: usize,
+    n_blocks: Vec<Range<usize>>,
+    mask_blocks: Vec<Range<usize>>,
+    /// The file offset of the packed bases
+    dna_offset: u64,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    name: String,
+    offset: u64,
+    /// Loaded on first access
+    record: Option<Record>,
+}
+
+/// A random-access reader for `.2bit` files.
+///
+/// The sequence index is read on construction, and each record header is read and
+/// cached the first time its sequence is accessed. Regions are read by seeking
+/// directly to their packed bases.
+#[derive(Debug)]
+pub struct TwoBitReader<R> {
+    reader: R,
+    big_endian: bool,
+    entries: Vec<Entry>,
+    lookup: HashMap<String, usize>,
+}
+
+impl TwoBitReader<BufReader<File>> {
+    /// Opens the `.2bit` file at `path` and reads its sequence index.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or is not a valid `.2bit` file.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, NucleotideError> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read + Seek> TwoBitReader<R> {
+    /// Creates a reader over a `.2bit` file and reads its sequence index.
+    ///
+    /// Files of either byte order and both the 32-bit (version 0) and 64-bit
+    /// (version 1) index layouts are accepted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NucleotideError::InvalidFormat`] if the signature or version is not
+    /// recognized or a name is not valid UTF-8, or any error from the underlying reader.
+    pub fn new(mut reader: R) -> Result<Self, NucleotideError> {
+        reader.seek(SeekFrom::Start(0))?;
+        let mut word = [0u8; 4];
+        reader.read_exact(&mut word)?;
+        let big_endian = if u32::from_le_bytes(word) == SIGNATURE {
+            false
+        } else if u32::from_be_bytes(word) == SIGNATURE {
+            true
+        } else {
+            return Err(invalid_format("not a .2bit file"));
+        };
+
+        let mut twobit = Self {
+            reader,
+            big_endian,
+            entries: Vec::new(),
+            lookup: HashMap::new(),
+        };
+        let version = twobit.read_u32()?;
+        if version > 1 {
+            return Err(invalid_format("unsupported .2bit version"));
+        }
+        let n_sequences = twobit.read_u32()? as usize;
+        twobit.read_u32()?;
+
+        for idx in 0..n_sequences {
+            let mut name_len = [0u8; 1];
+            twobit.reader.read_exact(&mut name_len)?;
+            let mut name = vec![0u8; name_len[0] as usize];
+            twobit.reader.read_exact(&mut name)?;
+            let name = String::from_utf8(name)
+                .map_err(|_| invalid_format("sequence name is not valid UTF-8"))?;
+            let offset = if version == 1 {
+                twobit.read_u64()?
+            } else {
+                twobit.read_u32()? as u64
+            };
+            twobit.lookup.insert(name.clone(), idx);
+            twobit.entries.push(Entry {
+                name,
+                offset,
+                record: None,
+            });
+        }
+        Ok(twobit)
+    }
+
+    /// Returns the number of sequences in the file.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the file contains no sequences.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the sequence names in file order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|entry| entry.name.as_str())
+    }
+
+    /// Returns the length of the named sequence.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NucleotideError::SequenceNotFound`] if the file has no sequence with
+    /// that name.
+    pub fn sequence_len(&mut self, name: &str) -> Result<usize, NucleotideError> {
+        Ok(self.record(name)?.length)
+    }
+
+    /// Reads the named sequence in full.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NucleotideError::SequenceNotFound`] if the file has no sequence with
+    /// that name.
+    pub fn read_sequence(&mut self, name: &str) -> Result<PackedSequence, NucleotideError> {
+        let length = self.sequence_len(name)?;
+        self.read_region(name, 0..length)
+    }
+
+    /// Reads a region of the named sequence, keeping its `N` runs and soft mask.
+    ///
+    /// Only the bytes holding the region are read from the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NucleotideError::SequenceNotFound`] if the file has no sequence with
+    /// that name, or [`NucleotideError::InvalidRange`] if the range is not within the
+    /// sequence.
+    pub fn read_region(
+        &mut self,
+        name: &str,
+        range: Range<usize>,
+    ) -> Result<PackedSequence, NucleotideError> {
+        let record = self.record(name)?;
+        if range.start > range.end || range.end > record.length {
+            return Err(NucleotideError::InvalidRange {
+                start: range.start,
+                end: range.end,
+                length: record.length,
+            });
+        }
+        let n_runs: Vec<_> = clip_runs(&record.n_blocks, range.clone()).collect();
+        let soft_mask: Vec<_> = clip_runs(&record.mask_blocks, range.clone()).collect();
+        let dna_offset = record.dna_offset;
+
+        let mut data = self.read_packed(dna_offset, range.clone())?;
+        clear_runs(&mut data, &n_runs);
+        Ok(PackedSequence::from_parts(
+            data,
+            range.len(),
+            n_runs,
+            soft_mask,
+        ))
+    }
+
+    /// Reads a region of the named sequence as ASCII, with `N` runs and soft-masked
+    /// bases restored.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`read_region`](Self::read_region).
+    pub fn read_region_ascii(
+        &mut self,
+        name: &str,
+        range: Range<usize>,
+    ) -> Result<Vec<u8>, NucleotideError> {
+        self.read_region(name, range)?.to_vec()
+    }
+
+    /// Returns the record header of the named sequence, reading it on first access.
+    fn record(&mut self, name: &str) -> Result<&Record, NucleotideError> {
+        let Some(&idx) = self.lookup.get(name) else {
+            return Err(NucleotideError::SequenceNotFound(name.to_string()));
+        };
+        if self.entries[idx].record.is_none() {
+            let record = self.read_record(self.entries[idx].offset)?;
+            self.entries[idx].record = Some(record);
+        }
+        Ok(self.entries[idx].record.as_ref().unwrap())
+    }
+
+    fn read_record(&mut self, offset: u64) -> Result<Record, NucleotideError> {
+        self.reader.seek(SeekFrom::Start(offset))?;
+        let length = self.read_u32()? as usize;
+        let n_blocks = self.read_blocks(length)?;
+        let mask_blocks = self.read_blocks(length)?;
+        self.read_u32()?;
+        Ok(Record {
+            length,
+            n_blocks,
+            mask_blocks,
+            dna_offset: self.reader.stream_position()?,
+        })
+    }
+
+    /// Reads a block list as sorted, non-overlapping runs.
+    fn read_blocks(&mut self, length: usize) -> Result<Vec<Range<usize>>, NucleotideError> {
+        let count = self.read_u32()? as usize;
+        let starts = self.read_u32s(count)?;
+        let sizes = self.read_u32s(count)?;
+
+        let mut blocks: Vec<Range<usize>> = starts
+            .into_iter()
+            .zip(sizes)
+            .filter(|&(_, size)| size > 0)
+            .map(|(start, size)| start as usize..start as usize + size as usize)
+            .collect();
+        blocks.sort_unstable_by_key(|block| block.start);
+
+        let mut runs: Vec<Range<usize>> = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            match runs.last_mut() {
+                Some(last) if block.start <= last.end => last.end = last.end.max(block.end),
+                _ => runs.push(block),
+            }
+        }
+        if runs.last().is_some_and(|run| run.end > length) {
+            return Err(invalid_format("block extends past the end of the sequence"));
+        }
+        Ok(runs)
+    }
+
+    /// Reads the packed bases of `range` into words of the crate's layout.
+    fn read_packed(
+        &mut self,
+        dna_offset: u64,
+        range: Range<usize>,
+    ) -> Result<Vec<u64>, NucleotideError> {
+        if range.is_empty() {
+            return Ok(Vec::new());
+        }
+        let first_byte = range.start / 4;
+        let mut bytes = vec![0u8; range.end.div_ceil(4) - first_byte];
+        self.reader
+            .seek(SeekFrom::Start(dna_offset + first_byte as u64))?;
+        self.reader.read_exact(&mut bytes)?;
+
+        let mut words: Vec<u64> = bytes
+            .chunks(8)
+            .map(|chunk| {
+                let mut word = [0u8; 8];
+                for (dst, &src) in word.iter_mut().zip(chunk) {
+                    *dst = FROM_TWOBIT[src as usize];
+                }
+                u64::from_le_bytes(word)
+            })
+            .collect();
+
+        // Drop the bases preceding the region in its first byte
+        let shift = (range.start % 4) * 2;
+        if shift > 0 {
+            for i in 0..words.len() {
+                let carry = words.get(i + 1).map_or(0, |next| next << (64 - shift));
+                words[i] = (words[i] >> shift) | carry;
+            }
+        }
+        words.truncate(range.len().div_ceil(32));
+        let tail = range.len() % 32;
+        if tail > 0 {
+            *words.last_mut().unwrap() &= (1 << (tail * 2)) - 1;
+        }
+        Ok(words)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, NucleotideError> {
+        let mut word = [0u8; 4];
+        self.reader.read_exact(&mut word)?;
+        Ok(if self.big_endian {
+            u32::from_be_bytes(word)
+        } else {
+            u32::from_le_bytes(word)
+        })
+    }
+
+    fn read_u64(&mut self) -> Result<u64, NucleotideError> {
+        let mut word = [0u8; 8];
+        self.reader.read_exact(&mut word)?;
+        Ok(if self.big_endian {
+            u64::from_be_bytes(word)
+        } else {
+            u64::from_le_bytes(word)
+        })
+    }
+
+    fn read_u32s(&mut self, count: usize) -> Result<Vec<u32>, NucleotideError> {
+        (0..count).map(|_| self.read_u32()).collect()
+    }
+}
+
+/// Writes sequences to a little-endian `.2bit` file.
+///
+/// The `N` runs and soft masks of the sequences are written as N-blocks and
+/// mask-blocks, and `N` bases are packed as `T` as UCSC tools do. The 64-bit index
+/// layout (version 1) is used only if the file would exceed 4 GiB.
+///
+/// # Errors
+///
+/// Returns [`NucleotideError::InvalidFormat`] if a name is empty or longer than 255
+/// bytes, [`NucleotideError::SequenceTooLong`] if a sequence is longer than `u32::MAX`
+/// bases, or any error from the writer.
+pub fn write_twobit<'a, W: Write>(
+    mut writer: W,
+    sequences: impl IntoIterator<Item = (&'a str, &'a PackedSequence)>,
+) -> Result<(), NucleotideError> {
+    let sequences: Vec<_> = sequences.into_iter().collect();
+    for (name, seq) in &sequences {
+        if name.is_empty() || name.len() > u8::MAX as usize {
+            return Err(NucleotideError::InvalidFormat(format!(
+                "invalid sequence name {name:?}"
+            )));
+        }
+        if seq.len() > u32::MAX as usize {
+            return Err(NucleotideError::SequenceTooLong(seq.len()));
+        }
+    }
+
+    let record_sizes: Vec<u64> = sequences
+        .iter()
+        .map(|(_, seq)| {
+            let n_blocks = seq.n_runs().len() + seq.soft_mask().len();
+            (16 + 8 * n_blocks + seq.len().div_ceil(4)) as u64
+        })
+        .collect();
+    let index_size = |offset_size: usize| -> u64 {
+        sequences
+            .iter()
+            .map(|(name, _)| (1 + name.len() + offset_size) as u64)
+            .sum()
+    };
+    let records_size: u64 = record_sizes.iter().sum();
+    let version = u32::from(16 + index_size(4) + records_size > u32::MAX as u64);
+
+    writer.write_all(&SIGNATURE.to_le_bytes())?;
+    writer.write_all(&version.to_le_bytes())?;
+    writer.write_all(&(sequences.len() as u32).to_le_bytes())?;
+    writer.write_all(&0u32.to_le_bytes())?;
+
+    let mut offset = 16 + index_size(if version == 1 { 8 } else { 4 });
+    for ((name, _), size) in sequences.iter().zip(&record_sizes) {
+        writer.write_all(&[name.len() as u8])?;
+        writer.write_all(name.as_bytes())?;
+        if version == 1 {
+            writer.write_all(&offset.to_le_bytes())?;
+        } else {
+            writer.write_all(&(offset as u32).to_le_bytes())?;
+        }
+        offset += size;
+    }
+
+    for (_, seq) in &sequences {
+        writer.write_all(&(seq.len() as u32).to_le_bytes())?;
+        write_blocks(&mut writer, seq.n_runs())?;
+        write_blocks(&mut writer, seq.soft_mask())?;
+        writer.write_all(&0u32.to_le_bytes())?;
+        writer.write_all(&pack_twobit(seq))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_blocks<W: Write>(writer: &mut W, runs: &[Range<usize>]) -> io::Result<()> {
+    writer.write_all(&(runs.len() as u32).to_le_bytes())?;
+    for run in runs {
+        writer.write_all(&(run.start as u32).to_le_bytes())?;
+    }
+    for run in runs {
+        writer.write_all(&(run.len() as u32).to_le_bytes())?;
+    }
+    Ok(())
+}
+
+/// Converts the packed words of a sequence into `.2bit` bytes.
+fn pack_twobit(seq: &PackedSequence) -> Vec<u8> {
+    let mut bytes: Vec<u8> = seq
+        .words()
+        .iter()
+        .flat_map(|word| word.to_le_bytes())
+        .take(seq.len().div_ceil(4))
+        .map(|byte| TO_TWOBIT[byte as usize])
+        .collect();
+
+    // The padding of the last byte is zeroed rather than packed as A
+    let tail = seq.len() % 4;
+    if tail > 0 {
+        *bytes.last_mut().unwrap() &= 0xFF << (8 - 2 * tail);
+    }
+
+    // N is stored as A in the words but as T (00) in the file
+    for run in seq.n_runs() {
+        for pos in run.clone() {
+            bytes[pos / 4] &= !(0b11 << (6 - 2 * (pos % 4)));
+        }
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// The sequences in the fixture files, written by hand from the format specification
+    const CHR1: &[u8] = b"ACGTnnNNacgtACGTTTGCAAGGCCTTAAcgtaCGTAGCTAGCTAGGATCCNNNNGA";
+    const CHRM: &[u8] = b"GATTACA";
+
+    const SMALL: &[u8] = include_bytes!("../tests/data/small.2bit");
+    const SMALL_BE: &[u8] = include_bytes!("../tests/data/small_be.2bit");
+    const SMALL_V1: &[u8] = include_bytes!("../tests/data/small_v1.2bit");
+
+    #[test]
+    fn test_lookup_tables() {
+        for byte in 0..=255u8 {
+            assert_eq!(TO_TWOBIT[FROM_TWOBIT[byte as usize] as usize], byte);
+        }
+        // TCAG in .2bit order
+        assert_eq!(
+            FROM_TWOBIT[0b00_01_10_11],
+            crate::as_2bit(b"TCAG").unwrap() as u8
+        );
+    }
+
+    #[test]
+    fn test_read_fixtures() {
+        for fixture in [SMALL, SMALL_BE, SMALL_V1] {
+            let mut reader = TwoBitReader::new(Cursor::new(fixture)).unwrap();
+            assert_eq!(reader.len(), 3);
+            assert_eq!(reader.names().collect::<Vec<_>>(), ["chr1", "chrM", "chrE"]);
+            assert_eq!(reader.sequence_len("chr1").unwrap(), CHR1.len());
+
+            let chr1 = reader.read_sequence("chr1").unwrap();
+            assert_eq!(chr1, PackedSequence::with_soft_mask(CHR1).unwrap());
+            assert_eq!(chr1.n_runs(), &[4..8, 52..56]);
+            assert_eq!(chr1.soft_mask(), &[4..6, 8..12, 30..34]);
+
+            let chrm = reader.read_sequence("chrM").unwrap();
+            assert_eq!(chrm.to_vec().unwrap(), CHRM);
+            assert!(reader.read_sequence("chrE").unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_read_regions() {
+        let mut reader = TwoBitReader::new(Cursor::new(SMALL)).unwrap();
+        for start in 0..CHR1.len() {
+            for end in start..=CHR1.len() {
+                let region = reader.read_region("chr1", start..end).unwrap();
+                assert_eq!(
+                    region,
+                    PackedSequence::with_soft_mask(&CHR1[start..end]).unwrap(),
+                    "{start}..{end}"
+                );
+            }
+        }
+        assert_eq!(reader.read_region_ascii("chrM", 1..5).unwrap(), b"ATTA");
+    }
+
+    #[test]
+    fn test_read_errors() {
+        let mut reader = TwoBitReader::new(Cursor::new(SMALL)).unwrap();
+        assert_eq!(
+            reader.read_sequence("chrX").unwrap_err(),
+            NucleotideError::SequenceNotFound("chrX".to_string())
+        );
+        assert_eq!(
+            reader.read_region("chrM", 2..8).unwrap_err(),
+            NucleotideError::InvalidRange {
+                start: 2,
+                end: 8,
+                length: 7
+            }
+        );
+
+        let mut corrupt = SMALL.to_vec();
+        corrupt[0] = 0;
+        assert_eq!(
+            TwoBitReader::new(Cursor::new(&corrupt)).unwrap_err(),
+            NucleotideError::InvalidFormat("not a .2bit file".to_string())
+        );
+        let err = TwoBitReader::new(Cursor::new(&SMALL[..10])).unwrap_err();
+        assert!(
+            matches!(err, NucleotideError::Io(err) if err.kind() == io::ErrorKind::UnexpectedEof)
+        );
+    }
+
+    #[test]
+    fn test_write_matches_fixture() {
+        let chr1 = PackedSequence::with_soft_mask(CHR1).unwrap();
+        let chrm = PackedSequence::new(CHRM).unwrap();
+        let chre = PackedSequence::new(b"").unwrap();
+
+        let mut file = Vec::new();
+        write_twobit(
+            &mut file,
+            [("chr1", &chr1), ("chrM", &chrm), ("chrE", &chre)],
+        )
+        .unwrap();
+        assert_eq!(file, SMALL);
+
+        assert!(matches!(
+            write_twobit(&mut file, [("", &chrm)]),
+            Err(NucleotideError::InvalidFormat(_))
+        ));
+    }
+}
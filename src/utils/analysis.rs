@@ -7,11 +7,16 @@ pub trait GCContent {
 impl GCContent for PackedSequence {
     fn gc_content(&self) -> f64 {
         let seq = self.to_vec().unwrap_or_default();
-        if seq.is_empty() {
+        // N bases are excluded from the denominator
+        let called = seq.len() - self.n_count();
+        if called == 0 {
             0.0
         } else {
-            let gc_count = seq.iter().filter(|&&b| b == b'G' || b == b'C').count();
-            (gc_count as f64 / self.len() as f64) * 100.0
+            let gc_count = seq
+                .iter()
+                .filter(|&&b| matches!(b, b'G' | b'g' | b'C' | b'c'))
+                .count();
+            (gc_count as f64 / called as f64) * 100.0
         }
     }
 }
@@ -26,10 +31,10 @@ impl BaseCount for PackedSequence {
         let mut counts = [0; 4];
         for &base in &seq {
             let idx = match base {
-                b'A' => 0,
-                b'C' => 1,
-                b'G' => 2,
-                b'T' => 3,
+                b'A' | b'a' => 0,
+                b'C' | b'c' => 1,
+                b'G' | b'g' => 2,
+                b'T' | b't' => 3,
                 _ => continue,
             };
             counts[idx] += 1;
@@ -75,6 +80,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_analysis_skips_n() {
+        let seq = PackedSequence::with_n(b"NNGCATNN").unwrap();
+        assert_eq!(seq.gc_content(), 50.0);
+        assert_eq!(seq.base_counts(), [1, 1, 1, 1]);
+
+        let seq = PackedSequence::with_n(b"NNNN").unwrap();
+        assert_eq!(seq.gc_content(), 0.0);
+        assert_eq!(seq.base_counts(), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_analysis_soft_masked() {
+        let seq = PackedSequence::with_soft_mask(b"acGTnn").unwrap();
+        assert_eq!(seq.gc_content(), 50.0);
+        assert_eq!(seq.base_counts(), [1, 1, 1, 1]);
+    }
+
     #[test]
     fn test_empty_sequence_analysis() {
         let seq = PackedSequence::new(b"").unwrap();
@@ -0,0 +1,249 @@
+use crate::utils::{decode, encode, from_2bit};
+use crate::NucleotideError;
+
+/// The number of words packed or unpacked per batch
+const BATCH_WORDS: usize = 64;
+
+/// Encode a sequence into a buffer of 2-bit encoded nucleotides, 4 bases per byte.
+///
+/// The bytes are identical to the little-endian byte view of `encode` output,
+/// truncated to `sequence.len().div_ceil(4)` bytes, and are packed with the same
+/// SIMD kernels.
+///
+/// # Arguments
+///
+/// * `sequence` - The nucleotide sequence to encode.
+/// * `bbuf` - The buffer to write the encoded nucleotides to.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{encode_alloc, encode_bytes};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut bbuf = Vec::new();
+/// encode_bytes(b"ACGTTGCAA", &mut bbuf)?;
+/// assert_eq!(bbuf, [0b11100100, 0b00011011, 0b00]);
+///
+/// let ebuf = encode_alloc(b"ACGTTGCAA")?;
+/// assert_eq!(bbuf, ebuf[0].to_le_bytes()[..3]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidBase` if the sequence contains a base other than
+/// A, C, G or T (case insensitive).
+pub fn encode_bytes(sequence: &[u8], bbuf: &mut Vec<u8>) -> Result<(), NucleotideError> {
+    bbuf.clear();
+    bbuf.reserve(sequence.len().div_ceil(4));
+
+    let mut words = Vec::with_capacity(BATCH_WORDS);
+    for (batch_idx, batch) in sequence.chunks(BATCH_WORDS * 32).enumerate() {
+        encode(batch, &mut words)
+            .map_err(|err| err.offset_position(batch_idx * BATCH_WORDS * 32))?;
+        let n_bytes = batch.len().div_ceil(4);
+        for (i, word) in words.iter().enumerate() {
+            let bytes = word.to_le_bytes();
+            bbuf.extend_from_slice(&bytes[..(n_bytes - i * 8).min(8)]);
+        }
+    }
+    Ok(())
+}
+
+/// Decode a buffer of 2-bit encoded nucleotides stored 4 bases per byte.
+///
+/// This is the inverse of [`encode_bytes`] and accepts the little-endian byte view
+/// of `encode` output.
+///
+/// # Arguments
+///
+/// * `bbuf` - The buffer containing the packed nucleotides.
+/// * `n_bases` - The number of nucleotides to unpack.
+/// * `dbuf` - The buffer to write the unpacked nucleotides to.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{decode_bytes, encode_bytes};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut bbuf = Vec::new();
+/// encode_bytes(b"ACGTTGCAA", &mut bbuf)?;
+///
+/// let mut dbuf = Vec::new();
+/// decode_bytes(&bbuf, 9, &mut dbuf)?;
+/// assert_eq!(dbuf, b"ACGTTGCAA");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidLength` if `bbuf` holds fewer than `n_bases` bases.
+pub fn decode_bytes(
+    bbuf: &[u8],
+    n_bases: usize,
+    dbuf: &mut Vec<u8>,
+) -> Result<(), NucleotideError> {
+    if bbuf.len() < n_bases.div_ceil(4) {
+        return Err(NucleotideError::InvalidLength(n_bases));
+    }
+    dbuf.reserve(n_bases);
+
+    // Full words are unpacked in batches with the SIMD decoders
+    let n_full = n_bases / 32;
+    let mut words = [0u64; BATCH_WORDS];
+    for batch in bbuf[..n_full * 8].chunks(BATCH_WORDS * 8) {
+        let n_words = batch.len() / 8;
+        for (word, bytes) in words.iter_mut().zip(batch.chunks_exact(8)) {
+            *word = u64::from_le_bytes(bytes.try_into().unwrap());
+        }
+        decode(&words[..n_words], n_words * 32, dbuf)?;
+    }
+
+    // The remaining bases occupy a partial word
+    let remainder = n_bases % 32;
+    if remainder > 0 {
+        let mut bytes = [0u8; 8];
+        let tail = &bbuf[n_full * 8..n_bases.div_ceil(4)];
+        bytes[..tail.len()].copy_from_slice(tail);
+        from_2bit(u64::from_le_bytes(bytes), remainder, dbuf)?;
+    }
+    Ok(())
+}
+
+/// Views a buffer of packed words as the bytes [`decode_bytes`] accepts, without copying.
+///
+/// Returns `None` on big-endian targets, where the in-memory bytes of a word are
+/// not in base order.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{decode_bytes, encode_alloc, words_as_bytes};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let ebuf = encode_alloc(b"ACGTTGCAA")?;
+/// # if cfg!(target_endian = "little") {
+/// let bytes = words_as_bytes(&ebuf).unwrap();
+///
+/// let mut dbuf = Vec::new();
+/// decode_bytes(bytes, 9, &mut dbuf)?;
+/// assert_eq!(dbuf, b"ACGTTGCAA");
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+pub fn words_as_bytes(words: &[u64]) -> Option<&[u8]> {
+    if cfg!(target_endian = "little") {
+        // SAFETY: u8 has no alignment requirement and every byte of a u64 is initialized
+        Some(unsafe { std::slice::from_raw_parts(words.as_ptr().cast(), words.len() * 8) })
+    } else {
+        None
+    }
+}
+
+/// Views a buffer of bytes from [`encode_bytes`] as packed words, without copying.
+///
+/// Returns `None` if the buffer is not 8-byte aligned, its length is not a multiple
+/// of 8, or the target is big-endian.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{bytes_as_words, encode_alloc, words_as_bytes};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let ebuf = encode_alloc(b"ACGTTGCAA")?;
+/// # if cfg!(target_endian = "little") {
+/// let bytes = words_as_bytes(&ebuf).unwrap();
+/// assert_eq!(bytes_as_words(bytes), Some(&ebuf[..]));
+/// assert_eq!(bytes_as_words(&bytes[1..]), None);
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+pub fn bytes_as_words(bytes: &[u8]) -> Option<&[u64]> {
+    if !cfg!(target_endian = "little") {
+        return None;
+    }
+    if bytes.as_ptr().align_offset(std::mem::align_of::<u64>()) != 0
+        || !bytes.len().is_multiple_of(8)
+    {
+        return None;
+    }
+    // SAFETY: the pointer is aligned for u64, the length covers whole words within
+    // `bytes`, and every bit pattern is a valid u64
+    Some(unsafe { std::slice::from_raw_parts(bytes.as_ptr().cast::<u64>(), bytes.len() / 8) })
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::encode_alloc;
+    use nucgen::Sequence;
+
+    #[test]
+    fn test_bytes_match_word_layout() {
+        let mut rng = rand::thread_rng();
+        let mut seq = Sequence::new();
+
+        for len in [1, 3, 4, 5, 31, 32, 33, 100, 2048, 2049, 5000] {
+            seq.fill_buffer(&mut rng, len);
+            let ebuf = encode_alloc(seq.bytes()).unwrap();
+
+            let mut bbuf = Vec::new();
+            encode_bytes(seq.bytes(), &mut bbuf).unwrap();
+            let expected: Vec<u8> = ebuf.iter().flat_map(|word| word.to_le_bytes()).collect();
+            assert_eq!(bbuf, expected[..len.div_ceil(4)]);
+
+            let mut dbuf = vec![b'>'];
+            decode_bytes(&bbuf, len, &mut dbuf).unwrap();
+            assert_eq!(&dbuf[1..], seq.bytes());
+
+            // The padded word bytes decode as well
+            dbuf.clear();
+            decode_bytes(&expected, len, &mut dbuf).unwrap();
+            assert_eq!(dbuf, seq.bytes());
+        }
+    }
+
+    #[test]
+    fn test_bytes_errors() {
+        let mut bbuf = Vec::new();
+        assert_eq!(
+            encode_bytes(b"ACGTN", &mut bbuf),
+            Err(NucleotideError::InvalidBase {
+                base: b'N',
+                position: 4
+            })
+        );
+        let mut seq = vec![b'A'; 5000];
+        seq[4321] = b'X';
+        assert_eq!(
+            encode_bytes(&seq, &mut bbuf),
+            Err(NucleotideError::InvalidBase {
+                base: b'X',
+                position: 4321
+            })
+        );
+        encode_bytes(b"", &mut bbuf).unwrap();
+        assert!(bbuf.is_empty());
+        assert_eq!(
+            decode_bytes(&[0; 2], 9, &mut Vec::new()),
+            Err(NucleotideError::InvalidLength(9))
+        );
+    }
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn test_bytes_views() {
+        let ebuf = encode_alloc(&[b'G'; 100]).unwrap();
+        let bytes = words_as_bytes(&ebuf).unwrap();
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(bytes_as_words(bytes).unwrap(), ebuf);
+        assert!(bytes_as_words(&bytes[..31]).is_none());
+    }
+}
@@ -0,0 +1,168 @@
+//! Runtime selection of the SIMD backend.
+//!
+//! The best instruction set for the running CPU is detected on first use and cached.
+//! Callers `match` on [`level`] and call the backend functions directly, so the choice
+//! costs one relaxed load and every backend stays inlinable. When the crate is compiled
+//! with AVX2 (x86_64) or NEON (aarch64) enabled, e.g. with `-C target-cpu=native`, the
+//! level is known statically and no detection happens at all. With the `nosimd`
+//! feature, or on other architectures, the naive backend is always chosen.
+
+#[cfg(any(
+    all(
+        target_arch = "x86_64",
+        not(feature = "nosimd"),
+        not(target_feature = "avx2")
+    ),
+    all(
+        target_arch = "aarch64",
+        not(feature = "nosimd"),
+        not(target_feature = "neon")
+    )
+))]
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// The instruction set used by the dispatched functions.
+///
+/// Every SIMD level is only returned by [`level`] once the CPU is known to support it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    any(target_feature = "avx2", target_feature = "neon"),
+    allow(dead_code)
+)]
+pub(crate) enum SimdLevel {
+    Naive = 1,
+    /// 128 bit instructions
+    #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+    Sse2 = 2,
+    /// 256 bit instructions
+    #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+    Avx2 = 3,
+    #[cfg(all(target_arch = "aarch64", not(feature = "nosimd")))]
+    Neon = 4,
+}
+
+/// The detected level, or zero before detection has run.
+#[cfg(any(
+    all(
+        target_arch = "x86_64",
+        not(feature = "nosimd"),
+        not(target_feature = "avx2")
+    ),
+    all(
+        target_arch = "aarch64",
+        not(feature = "nosimd"),
+        not(target_feature = "neon")
+    )
+))]
+static LEVEL: AtomicU8 = AtomicU8::new(0);
+
+/// Detects the best level supported by the running CPU and caches it.
+#[cfg(all(
+    target_arch = "x86_64",
+    not(feature = "nosimd"),
+    not(target_feature = "avx2")
+))]
+#[cold]
+fn detect() -> SimdLevel {
+    let level = if is_x86_feature_detected!("avx2") {
+        SimdLevel::Avx2
+    } else if is_x86_feature_detected!("sse2") {
+        SimdLevel::Sse2
+    } else {
+        SimdLevel::Naive
+    };
+    LEVEL.store(level as u8, Ordering::Relaxed);
+    level
+}
+
+/// Detects the best level supported by the running CPU and caches it.
+#[cfg(all(
+    target_arch = "aarch64",
+    not(feature = "nosimd"),
+    not(target_feature = "neon")
+))]
+#[cold]
+fn detect() -> SimdLevel {
+    let level = if std::arch::is_aarch64_feature_detected!("neon") {
+        SimdLevel::Neon
+    } else {
+        SimdLevel::Naive
+    };
+    LEVEL.store(level as u8, Ordering::Relaxed);
+    level
+}
+
+/// Returns the instruction set to use, detecting it on the first call.
+#[inline(always)]
+pub(crate) fn level() -> SimdLevel {
+    #[cfg(all(
+        target_arch = "x86_64",
+        not(feature = "nosimd"),
+        not(target_feature = "avx2")
+    ))]
+    match LEVEL.load(Ordering::Relaxed) {
+        0 => detect(),
+        level if level == SimdLevel::Avx2 as u8 => SimdLevel::Avx2,
+        level if level == SimdLevel::Sse2 as u8 => SimdLevel::Sse2,
+        _ => SimdLevel::Naive,
+    }
+
+    #[cfg(all(
+        target_arch = "aarch64",
+        not(feature = "nosimd"),
+        not(target_feature = "neon")
+    ))]
+    match LEVEL.load(Ordering::Relaxed) {
+        0 => detect(),
+        level if level == SimdLevel::Neon as u8 => SimdLevel::Neon,
+        _ => SimdLevel::Naive,
+    }
+
+    // Enabled at compile time, so always available
+    #[cfg(all(
+        target_arch = "x86_64",
+        not(feature = "nosimd"),
+        target_feature = "avx2"
+    ))]
+    {
+        SimdLevel::Avx2
+    }
+    #[cfg(all(
+        target_arch = "aarch64",
+        not(feature = "nosimd"),
+        target_feature = "neon"
+    ))]
+    {
+        SimdLevel::Neon
+    }
+
+    #[cfg(any(
+        feature = "nosimd",
+        all(not(target_arch = "aarch64"), not(target_arch = "x86_64"),)
+    ))]
+    SimdLevel::Naive
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn test_level_is_cached() {
+        let first = level();
+        assert_eq!(level(), first);
+
+        #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+        assert_eq!(first == SimdLevel::Avx2, is_x86_feature_detected!("avx2"));
+        #[cfg(all(target_arch = "aarch64", not(feature = "nosimd")))]
+        assert_eq!(
+            first == SimdLevel::Neon,
+            std::arch::is_aarch64_feature_detected!("neon")
+        );
+        #[cfg(any(
+            feature = "nosimd",
+            all(not(target_arch = "aarch64"), not(target_arch = "x86_64"),)
+        ))]
+        assert_eq!(first, SimdLevel::Naive);
+    }
+}
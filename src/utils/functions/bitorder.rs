@@ -0,0 +1,248 @@
+use crate::utils::word::PackedWord;
+use crate::{as_2bit, encode, from_2bit, NucleotideError};
+
+/// Moves `n_bases` LSB-first bases into MSB-first order within the low `2 * n_bases` bits.
+#[inline(always)]
+fn lsb_word_to_msb(packed: u64, n_bases: usize) -> u64 {
+    if n_bases == 0 {
+        return 0;
+    }
+    packed.reverse_2bit_groups() >> (64 - 2 * n_bases)
+}
+
+/// Moves `n_bases` MSB-first bases into LSB-first order within the low `2 * n_bases` bits.
+#[inline(always)]
+fn msb_word_to_lsb(packed: u64, n_bases: usize) -> u64 {
+    if n_bases == 0 {
+        return 0;
+    }
+    (packed << (64 - 2 * n_bases)).reverse_2bit_groups()
+}
+
+/// Converts a nucleotide sequence into a 2-bit packed representation with the first
+/// base in the most significant bits.
+///
+/// Unlike `as_2bit`, integer comparison of two packed sequences of the same length
+/// matches their lexicographic order, which makes the values suitable for sorted
+/// k-mer tables and binary search.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidBase` if the sequence contains any characters
+/// other than A,C,G,T (case insensitive).
+///
+/// Returns `NucleotideError::SequenceTooLong` if the input sequence is longer
+/// than 32 bases.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::as_2bit_msb;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// assert_eq!(as_2bit_msb(b"ACGT")?, 0b00011011);
+/// assert!(as_2bit_msb(b"CAAA")? < as_2bit_msb(b"GAAA")?);
+/// assert!(as_2bit_msb(b"AACT")? < as_2bit_msb(b"ACAA")?);
+/// # Ok(())
+/// # }
+/// ```
+#[inline(always)]
+pub fn as_2bit_msb(seq: &[u8]) -> Result<u64, NucleotideError> {
+    as_2bit(seq).map(|packed| lsb_word_to_msb(packed, seq.len()))
+}
+
+/// Converts an MSB-first 2-bit packed representation back into a nucleotide sequence.
+///
+/// This function reverses the packing performed by `as_2bit_msb`.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidLength` if `expected_size` is greater than 32.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{as_2bit_msb, from_2bit_msb};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut unpacked = Vec::new();
+/// from_2bit_msb(as_2bit_msb(b"GATTACA")?, 7, &mut unpacked)?;
+/// assert_eq!(unpacked, b"GATTACA");
+/// # Ok(())
+/// # }
+/// ```
+#[inline(always)]
+pub fn from_2bit_msb(
+    packed: u64,
+    expected_size: usize,
+    sequence: &mut Vec<u8>,
+) -> Result<(), NucleotideError> {
+    if expected_size > 32 {
+        return Err(NucleotideError::InvalidLength(expected_size));
+    }
+    from_2bit(
+        msb_word_to_lsb(packed, expected_size),
+        expected_size,
+        sequence,
+    )
+}
+
+/// Converts a buffer of LSB-first 2-bit encoded nucleotides to MSB-first in place.
+///
+/// Every word of the output is the `as_2bit_msb` packing of its 32 bases, so the last
+/// word holds the remaining bases in its lowest bits.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidLength` if `ebuf` holds fewer than `n_bases` bases.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{encode_alloc, encode_msb, lsb_to_msb};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let seq = b"ACGTACGTACGTACGTACGTACGTACGTACGTTTGCA";
+/// let mut ebuf = encode_alloc(seq)?;
+/// lsb_to_msb(&mut ebuf, seq.len())?;
+///
+/// let mut msb = Vec::new();
+/// encode_msb(seq, &mut msb)?;
+/// assert_eq!(ebuf, msb);
+/// # Ok(())
+/// # }
+/// ```
+pub fn lsb_to_msb(ebuf: &mut [u64], n_bases: usize) -> Result<(), NucleotideError> {
+    convert_words(ebuf, n_bases, lsb_word_to_msb)
+}
+
+/// Converts a buffer of MSB-first 2-bit encoded nucleotides to LSB-first in place.
+///
+/// This reverses [`lsb_to_msb`], so the output matches `encode` of the same sequence.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidLength` if `ebuf` holds fewer than `n_bases` bases.
+pub fn msb_to_lsb(ebuf: &mut [u64], n_bases: usize) -> Result<(), NucleotideError> {
+    convert_words(ebuf, n_bases, msb_word_to_lsb)
+}
+
+#[inline(always)]
+fn convert_words(
+    ebuf: &mut [u64],
+    n_bases: usize,
+    convert: fn(u64, usize) -> u64,
+) -> Result<(), NucleotideError> {
+    let n_chunks = n_bases.div_ceil(32);
+    if ebuf.len() < n_chunks {
+        return Err(NucleotideError::InvalidLength(n_bases));
+    }
+    for (i, word) in ebuf.iter_mut().take(n_chunks).enumerate() {
+        *word = convert(*word, (n_bases - i * 32).min(32));
+    }
+    Ok(())
+}
+
+/// Encode a sequence into a buffer of MSB-first 2-bit encoded nucleotides.
+///
+/// Each word holds the `as_2bit_msb` packing of 32 bases, with the last word holding
+/// the remainder in its lowest bits.
+///
+/// # Errors
+///
+/// If the sequence cannot be encoded, an error is returned.
+pub fn encode_msb(sequence: &[u8], ebuf: &mut Vec<u64>) -> Result<(), NucleotideError> {
+    encode(sequence, ebuf)?;
+    lsb_to_msb(ebuf, sequence.len())
+}
+
+/// Decode a buffer of MSB-first 2-bit encoded nucleotides.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidLength` if `ebuf` holds fewer than `n_bases` bases.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{decode_msb, encode_msb};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let seq = b"ACGTACGTACGTACGTACGTACGTACGTACGTTTGCA";
+/// let mut ebuf = Vec::new();
+/// encode_msb(seq, &mut ebuf)?;
+///
+/// let mut dbuf = Vec::new();
+/// decode_msb(&ebuf, seq.len(), &mut dbuf)?;
+/// assert_eq!(dbuf, seq);
+/// # Ok(())
+/// # }
+/// ```
+pub fn decode_msb(ebuf: &[u64], n_bases: usize, dbuf: &mut Vec<u8>) -> Result<(), NucleotideError> {
+    let n_chunks = n_bases.div_ceil(32);
+    if ebuf.len() < n_chunks {
+        return Err(NucleotideError::InvalidLength(n_bases));
+    }
+    dbuf.reserve(n_bases);
+    for (i, &packed) in ebuf.iter().take(n_chunks).enumerate() {
+        from_2bit_msb(packed, (n_bases - i * 32).min(32), dbuf)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use nucgen::Sequence;
+
+    #[test]
+    fn test_msb_sorts_lexicographically() {
+        let mut rng = rand::thread_rng();
+        let mut seq = Sequence::new();
+        seq.fill_buffer(&mut rng, 2000);
+
+        let mut kmers: Vec<&[u8]> = seq.bytes().windows(21).collect();
+        kmers.sort();
+        let packed: Vec<u64> = kmers.iter().map(|k| as_2bit_msb(k).unwrap()).collect();
+        assert!(packed.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn test_msb_roundtrip() {
+        let mut rng = rand::thread_rng();
+        let mut seq = Sequence::new();
+
+        for len in [1, 5, 31, 32, 33, 64, 65, 1000] {
+            seq.fill_buffer(&mut rng, len);
+
+            let mut ebuf = Vec::new();
+            encode_msb(seq.bytes(), &mut ebuf).unwrap();
+            let expected: Vec<u64> = seq
+                .bytes()
+                .chunks(32)
+                .map(|chunk| as_2bit_msb(chunk).unwrap())
+                .collect();
+            assert_eq!(ebuf, expected);
+
+            let mut dbuf = Vec::new();
+            decode_msb(&ebuf, len, &mut dbuf).unwrap();
+            assert_eq!(dbuf, seq.bytes());
+
+            msb_to_lsb(&mut ebuf, len).unwrap();
+            assert_eq!(ebuf, crate::encode_alloc(seq.bytes()).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_msb_errors() {
+        assert!(matches!(
+            from_2bit_msb(0, 33, &mut Vec::new()),
+            Err(NucleotideError::InvalidLength(33))
+        ));
+        assert!(matches!(
+            lsb_to_msb(&mut [0], 33),
+            Err(NucleotideError::InvalidLength(33))
+        ));
+        assert_eq!(as_2bit_msb(b"").unwrap(), 0);
+    }
+}
@@ -0,0 +1,298 @@
+use crate::{as_2bit, NucleotideError};
+
+/// Selects the bits of the last word of a buffer holding `n_bases` bases.
+#[inline(always)]
+fn last_word_mask(n_bases: usize) -> u64 {
+    match n_bases % 32 {
+        0 => u64::MAX,
+        rem => (1u64 << (rem * 2)) - 1,
+    }
+}
+
+/// Truncates `ebuf` to the words holding `n_bases` bases and clears the padding bits of
+/// its last word.
+fn trim_packed(ebuf: &mut Vec<u64>, n_bases: usize) -> Result<(), NucleotideError> {
+    let n_words = n_bases.div_ceil(32);
+    if ebuf.len() < n_words {
+        return Err(NucleotideError::InvalidLength(n_bases));
+    }
+    ebuf.truncate(n_words);
+    if let Some(last) = ebuf.last_mut() {
+        *last &= last_word_mask(n_bases);
+    }
+    Ok(())
+}
+
+/// Pushes a full word of bases onto `ebuf`, whose last word is filled up to bit `shift`.
+///
+/// The low bits of `word` fill the last word and the high bits carry into a new one.
+#[inline(always)]
+fn push_shifted(ebuf: &mut Vec<u64>, shift: usize, word: u64) {
+    if shift == 0 {
+        ebuf.push(word);
+    } else {
+        if let Some(last) = ebuf.last_mut() {
+            *last |= word << shift;
+        }
+        ebuf.push(word >> (64 - shift));
+    }
+}
+
+/// Encode a sequence onto the end of a buffer that already holds `current_len` bases.
+///
+/// Unlike `encode`, the buffer is not cleared: packing continues from the partially
+/// filled final word, so records can be concatenated into one contiguous stream without
+/// padding between them. The result is identical to `encode` of the concatenated bases.
+///
+/// # Arguments
+///
+/// * `sequence` - The nucleotide sequence to encode.
+/// * `ebuf` - The buffer to append the encoded nucleotides to.
+/// * `current_len` - The number of bases already in `ebuf`.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{encode_alloc, encode_append};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut ebuf = Vec::new();
+/// let mut n_bases = 0;
+/// for record in [&b"ACGTTGCA"[..], b"GATTACA", b"TTTTCCCCGGGGAAAATTTTCCCCGGGGAAAA"] {
+///     encode_append(record, &mut ebuf, n_bases)?;
+///     n_bases += record.len();
+/// }
+/// assert_eq!(ebuf, encode_alloc(b"ACGTTGCAGATTACATTTTCCCCGGGGAAAATTTTCCCCGGGGAAAA")?);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidLength` if `ebuf` holds fewer than `current_len` bases.
+///
+/// Returns `NucleotideError::InvalidBase` if the sequence contains a base other than
+/// A, C, G or T (case insensitive), with its position within `sequence`. The first
+/// `current_len` bases of `ebuf` are left intact.
+pub fn encode_append(
+    sequence: &[u8],
+    ebuf: &mut Vec<u64>,
+    current_len: usize,
+) -> Result<(), NucleotideError> {
+    trim_packed(ebuf, current_len)?;
+    ebuf.reserve((current_len + sequence.len()).div_ceil(32) - ebuf.len());
+
+    let shift = (current_len % 32) * 2;
+    for (i, chunk) in sequence.chunks(32).enumerate() {
+        match as_2bit(chunk) {
+            Ok(bits) => push_shifted(ebuf, shift, bits),
+            Err(err) => {
+                // Drop the partially appended bases
+                trim_packed(ebuf, current_len)?;
+                return Err(err.offset_position(i * 32));
+            }
+        }
+    }
+
+    // The final carry may have produced an empty word
+    ebuf.truncate((current_len + sequence.len()).div_ceil(32));
+    Ok(())
+}
+
+/// Joins two packed nucleotide sequences of arbitrary lengths into one.
+///
+/// This is the inverse of `split_packed`: the bases of `rbuf` are shifted into the
+/// padding of the last word of `lbuf`, and `obuf` is identical to `encode` of the
+/// concatenated bases.
+///
+/// # Arguments
+/// * `lbuf` - The encoded left sequence
+/// * `llen` - The length of the left sequence in bases
+/// * `rbuf` - The encoded right sequence
+/// * `rlen` - The length of the right sequence in bases
+/// * `obuf` - Buffer to store the joined sequence
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{concat_packed, encode_alloc};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let lbuf = encode_alloc(b"ACGTACGTA")?;
+/// let rbuf = encode_alloc(b"TTGCA")?;
+///
+/// let mut obuf = Vec::new();
+/// concat_packed(&lbuf, 9, &rbuf, 5, &mut obuf)?;
+/// assert_eq!(obuf, encode_alloc(b"ACGTACGTATTGCA")?);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// Returns `NucleotideError::InvalidLength` if either buffer holds fewer bases than its
+/// given length.
+pub fn concat_packed(
+    lbuf: &[u64],
+    llen: usize,
+    rbuf: &[u64],
+    rlen: usize,
+    obuf: &mut Vec<u64>,
+) -> Result<(), NucleotideError> {
+    obuf.clear();
+    obuf.reserve((llen + rlen).div_ceil(32));
+    obuf.extend_from_slice(&lbuf[..llen.div_ceil(32).min(lbuf.len())]);
+    append_packed(obuf, llen, rbuf, rlen)
+}
+
+/// Appends the first `n_bases` bases of `words` to `ebuf`, which holds `current_len` bases.
+pub(crate) fn append_packed(
+    ebuf: &mut Vec<u64>,
+    current_len: usize,
+    words: &[u64],
+    n_bases: usize,
+) -> Result<(), NucleotideError> {
+    let n_words = n_bases.div_ceil(32);
+    if words.len() < n_words {
+        return Err(NucleotideError::InvalidLength(n_bases));
+    }
+    trim_packed(ebuf, current_len)?;
+
+    let shift = (current_len % 32) * 2;
+    for (i, &word) in words[..n_words].iter().enumerate() {
+        let word = if i + 1 == n_words {
+            word & last_word_mask(n_bases)
+        } else {
+            word
+        };
+        push_shifted(ebuf, shift, word);
+    }
+
+    // The final carry may have produced an empty word
+    ebuf.truncate((current_len + n_bases).div_ceil(32));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{encode_alloc, split_packed};
+    use nucgen::Sequence;
+
+    const LENGTHS: [usize; 10] = [0, 1, 7, 31, 32, 33, 63, 64, 65, 150];
+
+    #[test]
+    fn test_encode_append_matches_encode() {
+        let mut rng = rand::thread_rng();
+        let mut left = Sequence::new();
+        let mut right = Sequence::new();
+
+        for llen in LENGTHS {
+            for rlen in LENGTHS {
+                left.fill_buffer(&mut rng, llen);
+                right.fill_buffer(&mut rng, rlen);
+                let mut joined = left.bytes().to_vec();
+                joined.extend_from_slice(right.bytes());
+
+                let mut ebuf = if llen == 0 {
+                    Vec::new()
+                } else {
+                    encode_alloc(left.bytes()).unwrap()
+                };
+                encode_append(right.bytes(), &mut ebuf, llen).unwrap();
+                if joined.is_empty() {
+                    assert!(ebuf.is_empty());
+                } else {
+                    assert_eq!(ebuf, encode_alloc(&joined).unwrap());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_append_ignores_stale_bits() {
+        // Bits past `current_len` are cleared before appending
+        let mut ebuf = encode_alloc(b"ACGTTTTT").unwrap();
+        ebuf.push(u64::MAX);
+        encode_append(b"CA", &mut ebuf, 4).unwrap();
+        assert_eq!(ebuf, encode_alloc(b"ACGTCA").unwrap());
+    }
+
+    #[test]
+    fn test_encode_append_errors() {
+        let mut ebuf = encode_alloc(b"ACGTA").unwrap();
+        let mut seq = vec![b'C'; 70];
+        seq[40] = b'N';
+        assert_eq!(
+            encode_append(&seq, &mut ebuf, 5),
+            Err(NucleotideError::InvalidBase {
+                base: b'N',
+                position: 40
+            })
+        );
+        assert_eq!(ebuf, encode_alloc(b"ACGTA").unwrap());
+
+        assert_eq!(
+            encode_append(b"ACGT", &mut ebuf, 33),
+            Err(NucleotideError::InvalidLength(33))
+        );
+    }
+
+    #[test]
+    fn test_concat_inverts_split() {
+        let mut rng = rand::thread_rng();
+        let mut seq = Sequence::new();
+        seq.fill_buffer(&mut rng, 150);
+        let ebuf = encode_alloc(seq.bytes()).unwrap();
+
+        let mut lbuf = Vec::new();
+        let mut rbuf = Vec::new();
+        let mut obuf = Vec::new();
+        for idx in [1, 10, 31, 32, 33, 64, 100, 149] {
+            split_packed(&ebuf, 150, idx, &mut lbuf, &mut rbuf).unwrap();
+            concat_packed(&lbuf, idx, &rbuf, 150 - idx, &mut obuf).unwrap();
+            assert_eq!(obuf, ebuf);
+        }
+    }
+
+    #[test]
+    fn test_concat_matches_encode() {
+        let mut rng = rand::thread_rng();
+        let mut left = Sequence::new();
+        let mut right = Sequence::new();
+        let mut obuf = Vec::new();
+
+        for llen in LENGTHS.into_iter().skip(1) {
+            for rlen in LENGTHS.into_iter().skip(1) {
+                left.fill_buffer(&mut rng, llen);
+                right.fill_buffer(&mut rng, rlen);
+                let lbuf = encode_alloc(left.bytes()).unwrap();
+                let rbuf = encode_alloc(right.bytes()).unwrap();
+
+                let mut joined = left.bytes().to_vec();
+                joined.extend_from_slice(right.bytes());
+                concat_packed(&lbuf, llen, &rbuf, rlen, &mut obuf).unwrap();
+                assert_eq!(obuf, encode_alloc(&joined).unwrap());
+
+                // An empty side leaves the other unchanged
+                concat_packed(&[], 0, &rbuf, rlen, &mut obuf).unwrap();
+                assert_eq!(obuf, rbuf);
+                concat_packed(&lbuf, llen, &[], 0, &mut obuf).unwrap();
+                assert_eq!(obuf, lbuf);
+            }
+        }
+    }
+
+    #[test]
+    fn test_concat_errors() {
+        let ebuf = encode_alloc(b"ACGT").unwrap();
+        let mut obuf = Vec::new();
+        assert_eq!(
+            concat_packed(&ebuf, 40, &ebuf, 4, &mut obuf),
+            Err(NucleotideError::InvalidLength(40))
+        );
+        assert_eq!(
+            concat_packed(&ebuf, 4, &ebuf, 40, &mut obuf),
+            Err(NucleotideError::InvalidLength(40))
+        );
+    }
+}
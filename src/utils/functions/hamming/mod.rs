@@ -1,5 +1,5 @@
 mod multi;
-mod scalar;
+pub(crate) mod scalar;
 
 pub use multi::hdist;
-pub use scalar::hdist_scalar;
+pub use scalar::{hdist_scalar, hdist_scalar_u128};
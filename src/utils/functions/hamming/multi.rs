@@ -3,6 +3,11 @@ use std::arch::aarch64::*;
 #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
 use std::arch::x86_64::*;
 
+#[cfg(any(
+    all(target_arch = "x86_64", not(feature = "nosimd")),
+    all(target_arch = "aarch64", not(feature = "nosimd"))
+))]
+use crate::utils::dispatch::{level, SimdLevel};
 use crate::NucleotideError;
 
 use super::hdist_scalar;
@@ -116,6 +121,32 @@ unsafe fn hdist_multi_neon(ebuf1: &[u64], ebuf2: &[u64], full_chunks: usize) ->
     total
 }
 
+/// Calculate the hamming distance over the first `full_chunks` words
+#[inline(always)]
+fn hdist_full_words(
+    ebuf1: &[u64],
+    ebuf2: &[u64],
+    full_chunks: usize,
+) -> Result<u32, NucleotideError> {
+    #[cfg(all(target_arch = "aarch64", not(feature = "nosimd")))]
+    if full_chunks >= 2 && level() == SimdLevel::Neon {
+        return Ok(unsafe { hdist_multi_neon(ebuf1, ebuf2, full_chunks) });
+    }
+
+    #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+    if full_chunks >= 4 && level() == SimdLevel::Avx2 {
+        return Ok(unsafe { hdist_multi_avx2(ebuf1, ebuf2, full_chunks) });
+    }
+
+    // Process word by word if SIMD is not available
+    ebuf1
+        .iter()
+        .zip(ebuf2)
+        .take(full_chunks)
+        .map(|(&u, &v)| hdist_scalar(u, v, 32))
+        .sum()
+}
+
 /// Calculate hamming distance between two 2-bit encoded sequences
 /// Each u64 contains up to 32 bases (2 bits per base)
 #[inline]
@@ -127,28 +158,7 @@ pub fn hdist(ebuf1: &[u64], ebuf2: &[u64], n_bases: usize) -> Result<u32, Nucleo
     }
 
     let full_chunks = n_bases / 32;
-    let mut total_dist = 0u32;
-
-    #[cfg(all(target_arch = "aarch64", not(feature = "nosimd")))]
-    unsafe {
-        if std::arch::is_aarch64_feature_detected!("neon") && full_chunks >= 2 {
-            total_dist = hdist_multi_neon(ebuf1, ebuf2, full_chunks);
-        }
-    }
-
-    #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
-    unsafe {
-        if is_x86_feature_detected!("avx2") && full_chunks >= 4 {
-            total_dist = hdist_multi_avx2(ebuf1, ebuf2, full_chunks);
-        }
-    }
-
-    // If SIMD is not available, use the naive implementation
-    if total_dist == 0 && full_chunks > 0 {
-        for (scal_ebuf1, scal_ebuf2) in ebuf1.iter().zip(ebuf2.iter()).take(full_chunks) {
-            total_dist += hdist_scalar(*scal_ebuf1, *scal_ebuf2, 32)?;
-        }
-    }
+    let mut total_dist = hdist_full_words(ebuf1, ebuf2, full_chunks)?;
 
     // Handle remaining bases
     let remaining_bases = n_bases % 32;
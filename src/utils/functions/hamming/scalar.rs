@@ -1,16 +1,12 @@
+use crate::utils::word::PackedWord;
 use crate::NucleotideError;
 
-// Create masks for lower and upper bits of each 2-bit group
-const LOWER_BITS: u64 = 0x5555555555555555;
-const UPPER_BITS: u64 = 0xAAAAAAAAAAAAAAAA;
-
-/// Calculate hamming distance between two 2-bit encoded u64 values
-/// Each u64 can contain up to 32 bases (2 bits per base)
-/// len must be <= 32
+/// Calculate hamming distance between two 2-bit encoded words of any width
+/// Each word can contain up to W::MAX_BASES bases (2 bits per base)
 #[inline]
-pub fn hdist_scalar(u: u64, v: u64, len: usize) -> Result<u32, NucleotideError> {
+pub fn hdist_word<W: PackedWord>(u: W, v: W, len: usize) -> Result<u32, NucleotideError> {
     // Validate length
-    if len > 32 {
+    if len > W::MAX_BASES {
         return Err(NucleotideError::InvalidLength(len));
     }
 
@@ -19,26 +15,16 @@ pub fn hdist_scalar(u: u64, v: u64, len: usize) -> Result<u32, NucleotideError>
         return Ok(0);
     }
 
-    // Calculate number of valid bits (2 bits per base)
-    let valid_bits = len * 2;
-
-    // Create mask for valid bits
-    let mask = if valid_bits == 64 {
-        u64::MAX
-    } else {
-        (1u64 << valid_bits) - 1
-    };
-
     // XOR to find differences and mask to valid region
-    let diff = (u ^ v) & mask;
+    let diff = (u ^ v) & W::mask(len);
 
-    if diff == 0 {
+    if diff == W::ZERO {
         return Ok(0);
     }
 
-    // Get differences in lower and upper bits, masked to valid region
-    let lower_diffs = diff & LOWER_BITS & mask;
-    let upper_diffs = (diff & UPPER_BITS & mask) >> 1;
+    // Get differences in lower and upper bits
+    let lower_diffs = diff & W::LOWER_BITS;
+    let upper_diffs = (diff >> 1) & W::LOWER_BITS;
 
     // Combine differences - if either or both bits differ, count as one difference
     let combined_diffs = lower_diffs | upper_diffs;
@@ -47,6 +33,22 @@ pub fn hdist_scalar(u: u64, v: u64, len: usize) -> Result<u32, NucleotideError>
     Ok(combined_diffs.count_ones())
 }
 
+/// Calculate hamming distance between two 2-bit encoded u64 values
+/// Each u64 can contain up to 32 bases (2 bits per base)
+/// len must be <= 32
+#[inline]
+pub fn hdist_scalar(u: u64, v: u64, len: usize) -> Result<u32, NucleotideError> {
+    hdist_word(u, v, len)
+}
+
+/// Calculate hamming distance between two 2-bit encoded u128 values
+/// Each u128 can contain up to 64 bases (2 bits per base)
+/// len must be <= 64
+#[inline]
+pub fn hdist_scalar_u128(u: u128, v: u128, len: usize) -> Result<u32, NucleotideError> {
+    hdist_word(u, v, len)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -87,6 +89,26 @@ mod tests {
         assert_eq!(hdist_scalar(ag, at, 2), Ok(1)); // AG vs AT = 1 difference
     }
 
+    #[test]
+    fn test_hdist_scalar_u128() {
+        assert!(hdist_scalar_u128(0, 0, 65).is_err());
+        assert_eq!(hdist_scalar_u128(0, 0, 0), Ok(0));
+
+        let seq1 = b"ACTGACTGACTGACTGACTGACTGACTGACTGACTGACTGACTGACTGACTGACTGACTGACTG";
+        let mut seq2 = *seq1;
+        seq2[0] = b'T';
+        seq2[33] = b'A';
+        seq2[63] = b'A';
+        let u = crate::as_2bit_u128(seq1).unwrap();
+        let v = crate::as_2bit_u128(&seq2).unwrap();
+        assert_eq!(hdist_scalar_u128(u, v, 64), Ok(3));
+        assert_eq!(hdist_scalar_u128(u, v, 63), Ok(2));
+        assert_eq!(
+            hdist_scalar_u128(u, v, 32),
+            hdist_scalar(u as u64, v as u64, 32)
+        );
+    }
+
     #[test]
     fn test_hdist_scalar_full_sequences() {
         // Test cases with known distances
@@ -1,5 +1,14 @@
-mod hamming;
+mod bitorder;
+mod concat;
+pub(crate) mod hamming;
+pub(crate) mod revcomp;
 mod split;
 
-pub use hamming::{hdist, hdist_scalar};
+pub use bitorder::{as_2bit_msb, decode_msb, encode_msb, from_2bit_msb, lsb_to_msb, msb_to_lsb};
+pub(crate) use concat::append_packed;
+pub use concat::{concat_packed, encode_append};
+pub use hamming::{hdist, hdist_scalar, hdist_scalar_u128};
+pub use revcomp::{
+    canonical_2bit, canonical_2bit_u128, revcomp_2bit, revcomp_2bit_u128, revcomp_packed,
+};
 pub use split::split_packed;
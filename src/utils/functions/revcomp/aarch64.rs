@@ -0,0 +1,62 @@
+use super::naive;
+use std::arch::aarch64::*;
+
+/// Reverse complements 2 full words and reverses their order.
+#[inline(always)]
+unsafe fn revcomp_2_words(v: uint64x2_t) -> uint64x2_t {
+    let x = vreinterpretq_u8_u64(v);
+
+    // Swap adjacent 2-bit groups within each nibble
+    let x = vorrq_u8(
+        vshrq_n_u8(vandq_u8(x, vdupq_n_u8(0xCC)), 2),
+        vshlq_n_u8(vandq_u8(x, vdupq_n_u8(0x33)), 2),
+    );
+    // Swap adjacent nibbles within each byte
+    let x = vorrq_u8(vshrq_n_u8(x, 4), vshlq_n_u8(x, 4));
+    // Reverse the bytes within each 64-bit lane
+    let x = vrev64q_u8(x);
+    // Complement
+    let x = vreinterpretq_u64_u8(vmvnq_u8(x));
+
+    // Swap the two 64-bit lanes
+    vextq_u64(x, x, 1)
+}
+
+pub unsafe fn revcomp_words(src: &[u64], dst: &mut [u64]) {
+    let n_words = src.len().min(dst.len());
+
+    // Process 2 words (64 bases) at a time
+    let dual_chunks = n_words / 2;
+    for i in 0..dual_chunks {
+        let s_ptr = src.as_ptr().add(n_words - (i + 1) * 2);
+        let d_ptr = dst.as_mut_ptr().add(i * 2);
+
+        let v = vld1q_u64(s_ptr);
+        vst1q_u64(d_ptr, revcomp_2_words(v));
+    }
+
+    // Handle remaining word
+    let remaining = n_words - dual_chunks * 2;
+    naive::revcomp_words(&src[..remaining], &mut dst[dual_chunks * 2..n_words]);
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn test_revcomp_words_matches_naive() {
+        let src: Vec<u64> = (0..7u64)
+            .map(|i| i.wrapping_mul(0x9E3779B97F4A7C15))
+            .collect();
+
+        let mut expected = vec![0u64; src.len()];
+        naive::revcomp_words(&src, &mut expected);
+
+        let mut observed = vec![0u64; src.len()];
+        unsafe {
+            revcomp_words(&src, &mut observed);
+        }
+        assert_eq!(observed, expected);
+    }
+}
@@ -0,0 +1,78 @@
+use super::naive;
+use std::arch::x86_64::*;
+
+/// Reverses the 2-bit groups within each byte of the vector using a nibble lookup.
+#[inline(always)]
+unsafe fn reverse_groups_in_bytes(v: __m256i) -> __m256i {
+    // Maps a nibble `ab` (two 2-bit groups) to `ba`
+    let lookup = _mm256_setr_epi8(
+        0x0, 0x4, 0x8, 0xC, 0x1, 0x5, 0x9, 0xD, 0x2, 0x6, 0xA, 0xE, 0x3, 0x7, 0xB, 0xF, 0x0, 0x4,
+        0x8, 0xC, 0x1, 0x5, 0x9, 0xD, 0x2, 0x6, 0xA, 0xE, 0x3, 0x7, 0xB, 0xF,
+    );
+    let low_nibbles = _mm256_set1_epi8(0x0F);
+
+    let lo = _mm256_and_si256(v, low_nibbles);
+    let hi = _mm256_and_si256(_mm256_srli_epi16(v, 4), low_nibbles);
+
+    // The reversed low nibble becomes the high nibble and vice versa
+    _mm256_or_si256(
+        _mm256_slli_epi16(_mm256_shuffle_epi8(lookup, lo), 4),
+        _mm256_shuffle_epi8(lookup, hi),
+    )
+}
+
+/// Reverse complements 4 full words and reverses their order.
+#[inline(always)]
+unsafe fn revcomp_4_words(v: __m256i) -> __m256i {
+    // Reverse bytes within each 64-bit lane
+    let byte_order = _mm256_setr_epi8(
+        7, 6, 5, 4, 3, 2, 1, 0, 15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 15, 14, 13,
+        12, 11, 10, 9, 8,
+    );
+    let reversed = _mm256_shuffle_epi8(reverse_groups_in_bytes(v), byte_order);
+
+    // Reverse the order of the 64-bit lanes
+    let reversed = _mm256_permute4x64_epi64(reversed, 0b00_01_10_11);
+
+    // Complement
+    _mm256_xor_si256(reversed, _mm256_set1_epi8(-1))
+}
+
+pub unsafe fn revcomp_words(src: &[u64], dst: &mut [u64]) {
+    let n_words = src.len().min(dst.len());
+
+    // Process 4 words (128 bases) at a time
+    let quad_chunks = n_words / 4;
+    for i in 0..quad_chunks {
+        let s_ptr = src.as_ptr().add(n_words - (i + 1) * 4);
+        let d_ptr = dst.as_mut_ptr().add(i * 4);
+
+        let v = _mm256_loadu_si256(s_ptr as *const __m256i);
+        _mm256_storeu_si256(d_ptr as *mut __m256i, revcomp_4_words(v));
+    }
+
+    // Handle remaining words
+    let remaining = n_words - quad_chunks * 4;
+    naive::revcomp_words(&src[..remaining], &mut dst[quad_chunks * 4..n_words]);
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn test_revcomp_words_matches_naive() {
+        let src: Vec<u64> = (0..11u64)
+            .map(|i| i.wrapping_mul(0x9E3779B97F4A7C15))
+            .collect();
+
+        let mut expected = vec![0u64; src.len()];
+        naive::revcomp_words(&src, &mut expected);
+
+        let mut observed = vec![0u64; src.len()];
+        unsafe {
+            revcomp_words(&src, &mut observed);
+        }
+        assert_eq!(observed, expected);
+    }
+}
@@ -0,0 +1,154 @@
+#[cfg(all(target_arch = "aarch64", not(feature = "nosimd")))]
+mod aarch64;
+#[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+mod avx;
+pub(crate) mod naive;
+#[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+mod sse;
+
+pub use naive::{canonical_2bit, canonical_2bit_u128, revcomp_2bit, revcomp_2bit_u128};
+
+use crate::utils::dispatch::{level, SimdLevel};
+use crate::NucleotideError;
+
+/// Writes the full 32-base reverse complement of each word in `src` into `dst`
+/// in reversed word order.
+#[inline(always)]
+fn revcomp_words(src: &[u64], dst: &mut [u64]) {
+    match level() {
+        #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+        SimdLevel::Avx2 => unsafe { avx::revcomp_words(src, dst) },
+        #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+        SimdLevel::Sse2 => unsafe { sse::revcomp_words(src, dst) },
+        #[cfg(all(target_arch = "aarch64", not(feature = "nosimd")))]
+        SimdLevel::Neon => unsafe { aarch64::revcomp_words(src, dst) },
+        SimdLevel::Naive => naive::revcomp_words(src, dst),
+    }
+}
+
+/// Calculates the reverse complement of a packed nucleotide sequence.
+///
+/// The sequence is expected to be packed as by `encode`, with 32 bases per u64
+/// and the final u64 holding the remainder. The output is packed in the same way.
+///
+/// # Arguments
+/// * `ebuf` - The encoded sequence buffer
+/// * `n_bases` - The total length of the sequence in bases
+/// * `out` - Buffer to store the reverse complement
+///
+/// # Errors
+/// Returns `NucleotideError::InvalidLength` if `ebuf` is too short to hold `n_bases`
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{decode, encode_alloc, revcomp_packed};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let seq = b"AACCGGTTTTGCAGCGATCGGCTAGGCAGTCAGATCG";
+/// let ebuf = encode_alloc(seq)?;
+///
+/// let mut rbuf = Vec::new();
+/// revcomp_packed(&ebuf, seq.len(), &mut rbuf)?;
+///
+/// let mut dbuf = Vec::new();
+/// decode(&rbuf, seq.len(), &mut dbuf)?;
+/// assert_eq!(&dbuf, b"CGATCTGACTGCCTAGCCGATCGCTGCAAAACCGGTT");
+/// # Ok(())
+/// # }
+/// ```
+pub fn revcomp_packed(
+    ebuf: &[u64],
+    n_bases: usize,
+    out: &mut Vec<u64>,
+) -> Result<(), NucleotideError> {
+    let n_chunks = n_bases.div_ceil(32);
+    if ebuf.len() < n_chunks {
+        return Err(NucleotideError::InvalidLength(n_bases));
+    }
+
+    // Clear output buffer
+    out.clear();
+    if n_chunks == 0 {
+        return Ok(());
+    }
+    out.resize(n_chunks, 0);
+
+    // Reverse complement every word as if it were full
+    revcomp_words(&ebuf[..n_chunks], out);
+
+    // The padding of the final input word is now at the start of the output,
+    // so shift the whole buffer down to drop it
+    let shift = (n_chunks * 32 - n_bases) * 2;
+    if shift > 0 {
+        for i in 0..n_chunks - 1 {
+            out[i] = (out[i] >> shift) | (out[i + 1] << (64 - shift));
+        }
+        out[n_chunks - 1] >>= shift;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode, encode_alloc};
+    use nucgen::Sequence;
+
+    fn naive_revcomp(seq: &[u8]) -> Vec<u8> {
+        seq.iter()
+            .rev()
+            .map(|b| match b {
+                b'A' => b'T',
+                b'C' => b'G',
+                b'G' => b'C',
+                b'T' => b'A',
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_revcomp_packed_various_lengths() {
+        let mut rng = rand::thread_rng();
+        let mut seq = Sequence::new();
+
+        let mut rbuf = Vec::new();
+        let mut dbuf = Vec::new();
+        for len in 1..=300 {
+            seq.fill_buffer(&mut rng, len);
+            let ebuf = encode_alloc(seq.bytes()).unwrap();
+
+            revcomp_packed(&ebuf, len, &mut rbuf).unwrap();
+            assert_eq!(rbuf.len(), ebuf.len());
+            assert_eq!(rbuf, encode_alloc(&naive_revcomp(seq.bytes())).unwrap());
+
+            dbuf.clear();
+            decode(&rbuf, len, &mut dbuf).unwrap();
+            assert_eq!(
+                dbuf,
+                naive_revcomp(seq.bytes()),
+                "Failed for length {}",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn test_revcomp_packed_empty() {
+        let mut rbuf = vec![1, 2, 3];
+        revcomp_packed(&[], 0, &mut rbuf).unwrap();
+        assert!(rbuf.is_empty());
+    }
+
+    #[test]
+    fn test_revcomp_packed_invalid_length() {
+        let ebuf = encode_alloc(b"ACGT").unwrap();
+        let mut rbuf = Vec::new();
+        assert_eq!(
+            revcomp_packed(&ebuf, 33, &mut rbuf),
+            Err(NucleotideError::InvalidLength(33))
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use crate::utils::word::PackedWord;
+
+/// Calculates the reverse complement of a 2-bit packed word of any width.
+#[inline(always)]
+pub fn revcomp_word<W: PackedWord>(packed: W, len: usize) -> W {
+    debug_assert!(
+        len <= W::MAX_BASES,
+        "length exceeds the capacity of the word"
+    );
+    if len == 0 {
+        return W::ZERO;
+    }
+    !packed.reverse_2bit_groups() >> (W::BITS - 2 * len)
+}
+
+/// Calculates the reverse complement of a 2-bit packed sequence.
+///
+/// The complement of a base is its bitwise inverse (A <-> T, C <-> G),
+/// so the reverse complement is the inverse of the sequence with its
+/// 2-bit groups in reversed order.
+///
+/// Bits beyond `len` bases are ignored and the result is zero-padded
+/// in the same way as `as_2bit`.
+///
+/// `len` must be <= 32.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{as_2bit, revcomp_2bit};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let packed = as_2bit(b"AACGT")?;
+/// assert_eq!(revcomp_2bit(packed, 5), as_2bit(b"ACGTT")?);
+/// # Ok(())
+/// # }
+/// ```
+#[inline(always)]
+pub fn revcomp_2bit(packed: u64, len: usize) -> u64 {
+    revcomp_word(packed, len)
+}
+
+/// Calculates the canonical form of a 2-bit packed k-mer.
+///
+/// The canonical k-mer is the smaller packed value of the k-mer and its reverse
+/// complement, so a k-mer and its reverse complement share the same canonical form.
+///
+/// `k` must be <= 32.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{as_2bit, canonical_2bit};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let fwd = as_2bit(b"TTGCA")?;
+/// let rev = as_2bit(b"TGCAA")?;
+/// assert_eq!(canonical_2bit(fwd, 5), canonical_2bit(rev, 5));
+/// # Ok(())
+/// # }
+/// ```
+#[inline(always)]
+pub fn canonical_2bit(kmer: u64, k: usize) -> u64 {
+    kmer.min(revcomp_2bit(kmer, k))
+}
+
+/// Calculates the reverse complement of a 2-bit packed `u128` sequence.
+///
+/// Behaves like `revcomp_2bit` for sequences of up to 64 bases.
+///
+/// `len` must be <= 64.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{as_2bit_u128, revcomp_2bit_u128};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let seq = b"AAAAAAAAAACCCCCCCCCCGGGGGGGGGGTTTTTTTTTTACGTA";
+/// let packed = as_2bit_u128(seq)?;
+/// assert_eq!(
+///     revcomp_2bit_u128(packed, seq.len()),
+///     as_2bit_u128(b"TACGTAAAAAAAAAACCCCCCCCCCGGGGGGGGGGTTTTTTTTTT")?
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[inline(always)]
+pub fn revcomp_2bit_u128(packed: u128, len: usize) -> u128 {
+    revcomp_word(packed, len)
+}
+
+/// Calculates the canonical form of a 2-bit packed `u128` k-mer.
+///
+/// Behaves like `canonical_2bit` for k-mers of up to 64 bases.
+///
+/// `k` must be <= 64.
+#[inline(always)]
+pub fn canonical_2bit_u128(kmer: u128, k: usize) -> u128 {
+    kmer.min(revcomp_2bit_u128(kmer, k))
+}
+
+/// Writes the full 32-base reverse complement of each word in `src` into `dst`
+/// in reversed word order.
+pub fn revcomp_words(src: &[u64], dst: &mut [u64]) {
+    for (d, &s) in dst.iter_mut().zip(src.iter().rev()) {
+        *d = !s.reverse_2bit_groups();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::as_2bit;
+
+    #[test]
+    fn test_revcomp_2bit() {
+        let tests: Vec<(&[u8], &[u8])> = vec![
+            (b"", b""),
+            (b"A", b"T"),
+            (b"C", b"G"),
+            (b"ACGT", b"ACGT"),
+            (b"AAAC", b"GTTT"),
+            (b"ACTGGAAAATTTTAAGG", b"CCTTAAAATTTTCCAGT"),
+            (
+                b"ACGTTGCAACGTTGCAACGTTGCAACGTTGCC",
+                b"GGCAACGTTGCAACGTTGCAACGTTGCAACGT",
+            ),
+        ];
+        for (seq, expected) in tests {
+            let packed = as_2bit(seq).unwrap();
+            assert_eq!(
+                revcomp_2bit(packed, seq.len()),
+                as_2bit(expected).unwrap(),
+                "Failed for {:?}",
+                std::str::from_utf8(seq).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_canonical_2bit() {
+        let fwd = as_2bit(b"GATTACA").unwrap();
+        let rev = as_2bit(b"TGTAATC").unwrap();
+        assert_eq!(canonical_2bit(fwd, 7), fwd.min(rev));
+        assert_eq!(canonical_2bit(rev, 7), fwd.min(rev));
+
+        // Palindromes are their own canonical form
+        let palindrome = as_2bit(b"ACGT").unwrap();
+        assert_eq!(canonical_2bit(palindrome, 4), palindrome);
+    }
+
+    #[test]
+    fn test_revcomp_2bit_u128() {
+        use crate::as_2bit_u128;
+
+        let seq = b"ACTGGAAAATTTTAAGGCCCGTAGCATGATCGATCGTAGCTAGTCGAGGCTTGAGGCCCATTC";
+        for len in 0..=seq.len() {
+            let rc: Vec<u8> = seq[..len]
+                .iter()
+                .rev()
+                .map(|b| match b {
+                    b'A' => b'T',
+                    b'C' => b'G',
+                    b'G' => b'C',
+                    _ => b'A',
+                })
+                .collect();
+            let packed = as_2bit_u128(&seq[..len]).unwrap();
+            let expected = as_2bit_u128(&rc).unwrap();
+            assert_eq!(revcomp_2bit_u128(packed, len), expected);
+            assert_eq!(canonical_2bit_u128(packed, len), packed.min(expected));
+        }
+    }
+
+    #[test]
+    fn test_revcomp_2bit_involution() {
+        let packed = as_2bit(b"ACTGGAAAATTTTAAGG").unwrap();
+        assert_eq!(revcomp_2bit(revcomp_2bit(packed, 17), 17), packed);
+    }
+}
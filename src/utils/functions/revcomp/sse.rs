@@ -0,0 +1,71 @@
+use super::naive;
+use std::arch::x86_64::*;
+
+/// Reverse complements 2 full words and reverses their order.
+///
+/// Only uses SSE2 instructions, so the 2-bit reversal is done with shifts
+/// rather than a byte shuffle.
+#[inline(always)]
+unsafe fn revcomp_2_words(v: __m128i) -> __m128i {
+    let pair_mask = _mm_set1_epi8(0x33);
+    let nibble_mask = _mm_set1_epi8(0x0F);
+
+    // Swap adjacent 2-bit groups within each nibble
+    let x = _mm_or_si128(
+        _mm_and_si128(_mm_srli_epi64(v, 2), pair_mask),
+        _mm_slli_epi64(_mm_and_si128(v, pair_mask), 2),
+    );
+    // Swap adjacent nibbles within each byte
+    let x = _mm_or_si128(
+        _mm_and_si128(_mm_srli_epi64(x, 4), nibble_mask),
+        _mm_slli_epi64(_mm_and_si128(x, nibble_mask), 4),
+    );
+    // Swap the bytes within each 16-bit word
+    let x = _mm_or_si128(_mm_slli_epi16(x, 8), _mm_srli_epi16(x, 8));
+    // Reverse the 16-bit words within each 64-bit lane
+    let x = _mm_shufflehi_epi16(_mm_shufflelo_epi16(x, 0b00_01_10_11), 0b00_01_10_11);
+    // Swap the two 64-bit lanes
+    let x = _mm_shuffle_epi32(x, 0b01_00_11_10);
+
+    // Complement
+    _mm_xor_si128(x, _mm_set1_epi8(-1))
+}
+
+pub unsafe fn revcomp_words(src: &[u64], dst: &mut [u64]) {
+    let n_words = src.len().min(dst.len());
+
+    // Process 2 words (64 bases) at a time
+    let dual_chunks = n_words / 2;
+    for i in 0..dual_chunks {
+        let s_ptr = src.as_ptr().add(n_words - (i + 1) * 2);
+        let d_ptr = dst.as_mut_ptr().add(i * 2);
+
+        let v = _mm_loadu_si128(s_ptr as *const __m128i);
+        _mm_storeu_si128(d_ptr as *mut __m128i, revcomp_2_words(v));
+    }
+
+    // Handle remaining word
+    let remaining = n_words - dual_chunks * 2;
+    naive::revcomp_words(&src[..remaining], &mut dst[dual_chunks * 2..n_words]);
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn test_revcomp_words_matches_naive() {
+        let src: Vec<u64> = (0..7u64)
+            .map(|i| i.wrapping_mul(0x9E3779B97F4A7C15))
+            .collect();
+
+        let mut expected = vec![0u64; src.len()];
+        naive::revcomp_words(&src, &mut expected);
+
+        let mut observed = vec![0u64; src.len()];
+        unsafe {
+            revcomp_words(&src, &mut observed);
+        }
+        assert_eq!(observed, expected);
+    }
+}
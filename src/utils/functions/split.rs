@@ -78,24 +78,14 @@ pub fn split_packed(
 
     // Handle remaining bits for right buffer
     let right_shift = bit_idx;
-    let mut carry = 0u64;
-
-    for curr in ebuf.iter().skip(chunk_idx) {
-        // Combine previous carry with current shifted value
-        let shifted = carry | (curr >> right_shift);
-        rbuf.push(shifted);
-
-        // Save bits that will be needed for next iteration
-        carry = if right_shift == 0 {
-            0
-        } else {
-            curr << (64 - right_shift)
-        };
-    }
 
-    // Handle the final carry if needed
-    if carry != 0 && rbuf.len() < right_chunks {
-        rbuf.push(carry);
+    for (offset, curr) in ebuf[chunk_idx..].iter().enumerate().take(right_chunks) {
+        // Carry the low bits of the next chunk into the high bits of this one
+        let carry = match ebuf.get(chunk_idx + offset + 1) {
+            Some(next) if right_shift > 0 => next << (64 - right_shift),
+            _ => 0,
+        };
+        rbuf.push(carry | (curr >> right_shift));
     }
 
     Ok(())
@@ -211,6 +201,29 @@ mod tests {
         assert_eq!(&right, &seq[32..]);
     }
 
+    #[test]
+    fn test_split_multiple_chunks() {
+        let seq = b"ACTGGAAAATTTTAAGGACTGGAAAATTTTAAGGCCCGTAGCATGATCGATCGTAGCTAGTCGTTGCAA"; // 69 bases
+        let mut ebuf = Vec::new();
+        encode(seq, &mut ebuf).unwrap();
+
+        let mut lbuf = Vec::new();
+        let mut rbuf = Vec::new();
+
+        for idx in [1, 10, 31, 33, 40, 63, 65, 68] {
+            split_packed(&ebuf, seq.len(), idx, &mut lbuf, &mut rbuf).unwrap();
+            assert_eq!(rbuf.len(), (seq.len() - idx).div_ceil(32));
+
+            let mut left = Vec::new();
+            decode(&lbuf, idx, &mut left).unwrap();
+            assert_eq!(&left, &seq[..idx]);
+
+            let mut right = Vec::new();
+            decode(&rbuf, seq.len() - idx, &mut right).unwrap();
+            assert_eq!(&right, &seq[idx..]);
+        }
+    }
+
     #[test]
     fn test_invalid_inputs() {
         let seq = b"ACTG";
@@ -0,0 +1,123 @@
+use super::naive;
+use crate::NucleotideError;
+use std::arch::aarch64::*;
+
+/// Builds a 4-bit code lookup for the lowercase ASCII bytes `0x60..=0x7F`.
+const fn letter_lookup() -> [u8; 32] {
+    let mut table = [0u8; 32];
+    let mut i = 0;
+    while i < 32 {
+        let code = naive::ENCODE_LOOKUP[0x60 + i];
+        // Gaps are handled separately, so zero marks an invalid byte
+        table[i] = if code == naive::INVALID_CODE { 0 } else { code };
+        i += 1;
+    }
+    table
+}
+
+static LETTERS: [u8; 32] = letter_lookup();
+
+/// Converts 16 ASCII bytes into 4-bit codes, returning `None` if any byte is invalid.
+#[inline(always)]
+unsafe fn codes_16(chunk: uint8x16_t) -> Option<uint8x16_t> {
+    let table = uint8x16x2_t(vld1q_u8(LETTERS.as_ptr()), vld1q_u8(LETTERS[16..].as_ptr()));
+    let lower = vorrq_u8(chunk, vdupq_n_u8(0x20));
+    // Out of range indices look up to zero
+    let codes = vqtbl2q_u8(table, vsubq_u8(lower, vdupq_n_u8(0x60)));
+
+    let is_gap = vceqq_u8(chunk, vdupq_n_u8(b'-'));
+    let valid = vorrq_u8(vtstq_u8(codes, codes), is_gap);
+    (vminvq_u8(valid) == 0xFF).then_some(codes)
+}
+
+/// Packs 16 code bytes into a word of 16 nibbles.
+#[inline(always)]
+unsafe fn pack_16(codes: uint8x16_t) -> u64 {
+    let even = vuzp1q_u8(codes, codes);
+    let odd = vuzp2q_u8(codes, codes);
+    let packed = vorrq_u8(even, vshlq_n_u8(odd, 4));
+    vgetq_lane_u64(vreinterpretq_u64_u8(packed), 0)
+}
+
+pub fn encode_internal(sequence: &[u8], ebuf: &mut Vec<u64>) -> Result<(), NucleotideError> {
+    ebuf.clear();
+    ebuf.reserve(sequence.len().div_ceil(16));
+
+    let simd_len = sequence.len() - (sequence.len() % 16);
+    unsafe {
+        for chunk_idx in (0..simd_len).step_by(16) {
+            let chunk = vld1q_u8(sequence[chunk_idx..].as_ptr());
+            match codes_16(chunk) {
+                Some(codes) => ebuf.push(pack_16(codes)),
+                // Let the scalar path locate the invalid byte
+                None => {
+                    naive::as_4bit(&sequence[chunk_idx..chunk_idx + 16])?;
+                }
+            }
+        }
+    }
+
+    if simd_len < sequence.len() {
+        ebuf.push(naive::as_4bit(&sequence[simd_len..])?);
+    }
+    Ok(())
+}
+
+pub fn decode_internal(
+    ebuf: &[u64],
+    n_bases: usize,
+    dbuf: &mut Vec<u8>,
+) -> Result<(), NucleotideError> {
+    let n_chunks = n_bases.div_ceil(16);
+    if ebuf.len() < n_chunks {
+        return Err(NucleotideError::InvalidLength(n_bases));
+    }
+    dbuf.reserve(n_bases);
+
+    // Two full words decode into 32 bytes at a time
+    let n_pairs = n_bases / 32;
+    unsafe {
+        let lookup = vld1q_u8(naive::DECODE_LOOKUP.as_ptr());
+        let mut temp = [0u8; 32];
+        for pair in ebuf.chunks_exact(2).take(n_pairs) {
+            let packed =
+                vreinterpretq_u8_u64(vcombine_u64(vcreate_u64(pair[0]), vcreate_u64(pair[1])));
+            let lo = vandq_u8(packed, vdupq_n_u8(0x0F));
+            let hi = vshrq_n_u8(packed, 4);
+            vst1q_u8(temp.as_mut_ptr(), vqtbl1q_u8(lookup, vzip1q_u8(lo, hi)));
+            vst1q_u8(
+                temp[16..].as_mut_ptr(),
+                vqtbl1q_u8(lookup, vzip2q_u8(lo, hi)),
+            );
+            dbuf.extend_from_slice(&temp);
+        }
+    }
+
+    naive::decode_internal(&ebuf[n_pairs * 2..], n_bases - n_pairs * 32, dbuf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neon_matches_naive() {
+        let alphabet = b"ACGTRYSWKMBDHVN-acgtrysw";
+        let seq: Vec<u8> = (0..517)
+            .map(|i| alphabet[(i * 7) % alphabet.len()])
+            .collect();
+        for len in [0, 15, 16, 31, 32, 33, 100, 517] {
+            let mut expected = Vec::new();
+            naive::encode_internal(&seq[..len], &mut expected).unwrap();
+            let mut ebuf = Vec::new();
+            encode_internal(&seq[..len], &mut ebuf).unwrap();
+            assert_eq!(ebuf, expected);
+
+            let mut expected = Vec::new();
+            naive::decode_internal(&ebuf, len, &mut expected).unwrap();
+            let mut dbuf = Vec::new();
+            decode_internal(&ebuf, len, &mut dbuf).unwrap();
+            assert_eq!(dbuf, expected);
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use super::naive;
+use crate::NucleotideError;
+use std::arch::x86_64::*;
+
+/// Builds a 4-bit code lookup for the lowercase ASCII bytes `0xH0..=0xHF`.
+const fn row_lookup(high: u8) -> [u8; 16] {
+    let mut row = [0u8; 16];
+    let mut i = 0;
+    while i < 16 {
+        let code = naive::ENCODE_LOOKUP[((high << 4) | i as u8) as usize];
+        // Gaps are handled separately, so zero marks an invalid byte
+        row[i] = if code == naive::INVALID_CODE { 0 } else { code };
+        i += 1;
+    }
+    row
+}
+
+static ROW_6: [u8; 16] = row_lookup(0x6);
+static ROW_7: [u8; 16] = row_lookup(0x7);
+
+/// Converts 32 ASCII bytes into 4-bit codes, returning the codes and a validity mask.
+#[inline(always)]
+unsafe fn codes_32(chunk: __m256i) -> (__m256i, i32) {
+    let row_6 = _mm256_broadcastsi128_si256(_mm_loadu_si128(ROW_6.as_ptr() as *const __m128i));
+    let row_7 = _mm256_broadcastsi128_si256(_mm_loadu_si128(ROW_7.as_ptr() as *const __m128i));
+    let low_nibble = _mm256_set1_epi8(0x0F);
+
+    let lower = _mm256_or_si256(chunk, _mm256_set1_epi8(0x20));
+    let index = _mm256_and_si256(lower, low_nibble);
+    let high = _mm256_and_si256(_mm256_srli_epi16(lower, 4), low_nibble);
+
+    let from_6 = _mm256_and_si256(
+        _mm256_cmpeq_epi8(high, _mm256_set1_epi8(0x6)),
+        _mm256_shuffle_epi8(row_6, index),
+    );
+    let from_7 = _mm256_and_si256(
+        _mm256_cmpeq_epi8(high, _mm256_set1_epi8(0x7)),
+        _mm256_shuffle_epi8(row_7, index),
+    );
+    let codes = _mm256_or_si256(from_6, from_7);
+
+    let is_gap = _mm256_cmpeq_epi8(chunk, _mm256_set1_epi8(b'-' as i8));
+    let is_zero = _mm256_cmpeq_epi8(codes, _mm256_setzero_si256());
+    let invalid = _mm256_andnot_si256(is_gap, is_zero);
+    (codes, _mm256_movemask_epi8(invalid))
+}
+
+/// Packs 32 code bytes into two words of 16 nibbles each.
+#[inline(always)]
+unsafe fn pack_32(codes: __m256i) -> (u64, u64) {
+    // Merge each odd code into the high nibble of its even neighbour
+    let pairs = _mm256_or_si256(
+        _mm256_and_si256(codes, _mm256_set1_epi16(0x00FF)),
+        _mm256_srli_epi16(codes, 4),
+    );
+    let packed = _mm256_packus_epi16(pairs, pairs);
+    (
+        _mm256_extract_epi64(packed, 0) as u64,
+        _mm256_extract_epi64(packed, 2) as u64,
+    )
+}
+
+pub fn encode_internal(sequence: &[u8], ebuf: &mut Vec<u64>) -> Result<(), NucleotideError> {
+    ebuf.clear();
+    ebuf.reserve(sequence.len().div_ceil(16));
+
+    let simd_len = sequence.len() - (sequence.len() % 32);
+    unsafe {
+        for chunk_idx in (0..simd_len).step_by(32) {
+            let chunk = _mm256_loadu_si256(sequence[chunk_idx..].as_ptr() as *const __m256i);
+            let (codes, invalid) = codes_32(chunk);
+            if invalid != 0 {
+                let position = chunk_idx + invalid.trailing_zeros() as usize;
+                return Err(NucleotideError::InvalidBase {
+                    base: sequence[position],
+                    position,
+                });
+            }
+            let (lo, hi) = pack_32(codes);
+            ebuf.push(lo);
+            ebuf.push(hi);
+        }
+    }
+
+    for (i, chunk) in sequence[simd_len..].chunks(16).enumerate() {
+        let offset = simd_len + i * 16;
+        ebuf.push(naive::as_4bit(chunk).map_err(|err| err.offset_position(offset))?);
+    }
+    Ok(())
+}
+
+pub fn decode_internal(
+    ebuf: &[u64],
+    n_bases: usize,
+    dbuf: &mut Vec<u8>,
+) -> Result<(), NucleotideError> {
+    let n_chunks = n_bases.div_ceil(16);
+    if ebuf.len() < n_chunks {
+        return Err(NucleotideError::InvalidLength(n_bases));
+    }
+    dbuf.reserve(n_bases);
+
+    // Two full words decode into 32 bytes at a time
+    let n_pairs = n_bases / 32;
+    unsafe {
+        let lookup = _mm_loadu_si128(naive::DECODE_LOOKUP.as_ptr() as *const __m128i);
+        let low_nibble = _mm_set1_epi8(0x0F);
+        let mut temp = [0u8; 32];
+        for pair in ebuf.chunks_exact(2).take(n_pairs) {
+            let packed = _mm_set_epi64x(pair[1] as i64, pair[0] as i64);
+            let lo = _mm_and_si128(packed, low_nibble);
+            let hi = _mm_and_si128(_mm_srli_epi16(packed, 4), low_nibble);
+            let first = _mm_shuffle_epi8(lookup, _mm_unpacklo_epi8(lo, hi));
+            let second = _mm_shuffle_epi8(lookup, _mm_unpackhi_epi8(lo, hi));
+            _mm_storeu_si128(temp.as_mut_ptr() as *mut __m128i, first);
+            _mm_storeu_si128(temp[16..].as_mut_ptr() as *mut __m128i, second);
+            dbuf.extend_from_slice(&temp);
+        }
+    }
+
+    naive::decode_internal(&ebuf[n_pairs * 2..], n_bases - n_pairs * 32, dbuf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_avx_matches_naive() {
+        if !is_x86_feature_detected!("avx2") {
+            return;
+        }
+        let alphabet = b"ACGTRYSWKMBDHVN-acgtrysw";
+        let seq: Vec<u8> = (0..517)
+            .map(|i| alphabet[(i * 7) % alphabet.len()])
+            .collect();
+        for len in [0, 15, 16, 31, 32, 33, 64, 100, 517] {
+            let mut expected = Vec::new();
+            naive::encode_internal(&seq[..len], &mut expected).unwrap();
+            let mut ebuf = Vec::new();
+            encode_internal(&seq[..len], &mut ebuf).unwrap();
+            assert_eq!(ebuf, expected);
+
+            let mut expected = Vec::new();
+            naive::decode_internal(&ebuf, len, &mut expected).unwrap();
+            let mut dbuf = Vec::new();
+            decode_internal(&ebuf, len, &mut dbuf).unwrap();
+            assert_eq!(dbuf, expected);
+        }
+    }
+
+    #[test]
+    fn test_avx_invalid_base() {
+        if !is_x86_feature_detected!("avx2") {
+            return;
+        }
+        let mut seq = vec![b'N'; 64];
+        for invalid in [b'X', b'\r', b'@', b'{', b'U', 0xE1] {
+            seq[37] = invalid;
+            let mut ebuf = Vec::new();
+            assert_eq!(
+                encode_internal(&seq, &mut ebuf),
+                Err(NucleotideError::InvalidBase {
+                    base: invalid,
+                    position: 37
+                })
+            );
+        }
+    }
+}
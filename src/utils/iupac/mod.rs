@@ -0,0 +1,377 @@
+//! 4-bit encoding for IUPAC nucleotide codes.
+//!
+//! Each base is stored as a nibble with one bit per nucleotide it may stand for:
+//! A = 0001, C = 0010, G = 0100, T = 1000. Ambiguity codes set several bits
+//! (R = A|G = 0101, N = 1111) and a gap (`-`) is stored as 0000.
+//!
+//! Bases are packed from least significant to most significant bits, 16 bases per `u64`,
+//! mirroring the layout of the 2-bit functions.
+//!
+//! ```rust
+//! use bitnuc::{encode_alloc, iupac};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let probe = iupac::encode_alloc(b"ACGRYN")?;
+//!
+//! // A primer matches every sequence its ambiguity codes admit
+//! assert!(iupac::is_compatible(&probe, &encode_alloc(b"ACGATG")?, 6)?);
+//! assert!(!iupac::is_compatible(&probe, &encode_alloc(b"ACGCTG")?, 6)?);
+//!
+//! let mut dbuf = Vec::new();
+//! iupac::decode(&probe, 6, &mut dbuf)?;
+//! assert_eq!(dbuf, b"ACGRYN");
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(all(target_arch = "aarch64", not(feature = "nosimd")))]
+mod aarch64;
+#[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+mod avx;
+mod naive;
+#[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+mod sse;
+
+use crate::utils::dispatch::{level, SimdLevel};
+use crate::NucleotideError;
+use naive::{nibble_mask, NIBBLE_LOW};
+
+/// Converts a sequence of up to 16 IUPAC codes into a 4-bit packed representation.
+///
+/// Accepts `A`, `C`, `G`, `T`, the ambiguity codes `R`, `Y`, `S`, `W`, `K`, `M`, `B`,
+/// `D`, `H`, `V`, `N` (case insensitive) and the gap `-`.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidBase` if the sequence contains any other character.
+///
+/// Returns `NucleotideError::SequenceTooLong` if the input sequence is longer
+/// than 16 bases.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::iupac;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let packed = iupac::as_4bit(b"ACGR")?;
+/// assert_eq!(packed, 0b0101_0100_0010_0001);
+/// # Ok(())
+/// # }
+/// ```
+#[inline(always)]
+pub fn as_4bit(seq: &[u8]) -> Result<u64, NucleotideError> {
+    naive::as_4bit(seq)
+}
+
+/// Converts a 4-bit packed representation back into IUPAC codes.
+///
+/// Ambiguity codes are decoded as uppercase letters.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidLength` if `expected_size` is greater than 16.
+pub fn from_4bit(
+    packed: u64,
+    expected_size: usize,
+    sequence: &mut Vec<u8>,
+) -> Result<(), NucleotideError> {
+    naive::from_4bit(packed, expected_size, sequence)
+}
+
+/// Encode a sequence of IUPAC codes into a buffer of 4-bit encoded nucleotides.
+///
+/// The buffer is cleared first and holds 16 bases per `u64`, with the last word
+/// zero-padded.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidBase` if the sequence contains a character that is
+/// not an IUPAC code.
+pub fn encode(sequence: &[u8], ebuf: &mut Vec<u64>) -> Result<(), NucleotideError> {
+    match level() {
+        #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+        SimdLevel::Avx2 => avx::encode_internal(sequence, ebuf),
+        #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+        SimdLevel::Sse2 => sse::encode_internal(sequence, ebuf),
+        #[cfg(all(target_arch = "aarch64", not(feature = "nosimd")))]
+        SimdLevel::Neon => aarch64::encode_internal(sequence, ebuf),
+        SimdLevel::Naive => naive::encode_internal(sequence, ebuf),
+    }
+}
+
+/// Encode a sequence of IUPAC codes into a newly allocated 4-bit buffer.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidBase` if the sequence contains a character that is
+/// not an IUPAC code.
+pub fn encode_alloc(sequence: &[u8]) -> Result<Vec<u64>, NucleotideError> {
+    let mut ebuf = Vec::new();
+    encode(sequence, &mut ebuf)?;
+    Ok(ebuf)
+}
+
+/// Decode a buffer of 4-bit encoded nucleotides into IUPAC codes.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidLength` if `ebuf` holds fewer than `n_bases` bases.
+pub fn decode(ebuf: &[u64], n_bases: usize, dbuf: &mut Vec<u8>) -> Result<(), NucleotideError> {
+    match level() {
+        #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+        SimdLevel::Avx2 => avx::decode_internal(ebuf, n_bases, dbuf),
+        #[cfg(all(target_arch = "aarch64", not(feature = "nosimd")))]
+        SimdLevel::Neon => aarch64::decode_internal(ebuf, n_bases, dbuf),
+        // No SSE kernel
+        #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+        SimdLevel::Sse2 => naive::decode_internal(ebuf, n_bases, dbuf),
+        SimdLevel::Naive => naive::decode_internal(ebuf, n_bases, dbuf),
+    }
+}
+
+/// Converts a 4-bit encoded buffer into a 2-bit encoded buffer.
+///
+/// The conversion is lossless: it succeeds only if every base is exactly one of
+/// A, C, G or T, and the output is identical to `encode` of the decoded sequence.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidBase` with the first ambiguity code or gap in the sequence.
+///
+/// Returns `NucleotideError::InvalidLength` if `ebuf` holds fewer than `n_bases` bases.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{encode_alloc, iupac, NucleotideError};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut out = Vec::new();
+/// iupac::to_2bit(&iupac::encode_alloc(b"ACGTTGCA")?, 8, &mut out)?;
+/// assert_eq!(out, encode_alloc(b"ACGTTGCA")?);
+///
+/// let result = iupac::to_2bit(&iupac::encode_alloc(b"ACGYTGCA")?, 8, &mut out);
+/// assert_eq!(
+///     result,
+///     Err(NucleotideError::InvalidBase { base: b'Y', position: 3 })
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_2bit(ebuf: &[u64], n_bases: usize, out: &mut Vec<u64>) -> Result<(), NucleotideError> {
+    if ebuf.len() < n_bases.div_ceil(16) {
+        return Err(NucleotideError::InvalidLength(n_bases));
+    }
+    out.clear();
+    out.reserve(n_bases.div_ceil(32));
+
+    for (i, words) in ebuf.chunks(2).take(n_bases.div_ceil(32)).enumerate() {
+        let mut packed = 0u64;
+        for (half, &word) in words.iter().enumerate() {
+            let start = i * 32 + half * 16;
+            if start >= n_bases {
+                break;
+            }
+            let n_word = (n_bases - start).min(16);
+            let codes =
+                naive::onehot_to_2bit(word, n_word).map_err(|j| NucleotideError::InvalidBase {
+                    base: naive::DECODE_LOOKUP[((word >> (j * 4)) & 0xF) as usize],
+                    position: start + j,
+                })?;
+            packed |= (codes as u64) << (half * 32);
+        }
+        out.push(packed);
+    }
+    Ok(())
+}
+
+/// Converts a 2-bit encoded buffer into a 4-bit encoded buffer.
+///
+/// Every base maps to exactly one nucleotide, so this conversion always succeeds
+/// for a buffer of the right size.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidLength` if `ebuf` holds fewer than `n_bases` bases.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{encode_alloc, iupac};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut out = Vec::new();
+/// iupac::from_2bit(&encode_alloc(b"ACGTTGCA")?, 8, &mut out)?;
+/// assert_eq!(out, iupac::encode_alloc(b"ACGTTGCA")?);
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_2bit(ebuf: &[u64], n_bases: usize, out: &mut Vec<u64>) -> Result<(), NucleotideError> {
+    if ebuf.len() < n_bases.div_ceil(32) {
+        return Err(NucleotideError::InvalidLength(n_bases));
+    }
+    out.clear();
+    out.reserve(n_bases.div_ceil(16));
+
+    for i in 0..n_bases.div_ceil(16) {
+        let codes = (ebuf[i / 2] >> ((i % 2) * 32)) as u32;
+        let n_word = (n_bases - i * 16).min(16);
+        out.push(naive::onehot_from_2bit(codes) & nibble_mask(n_word));
+    }
+    Ok(())
+}
+
+/// Returns true if every base of the 2-bit sequence is admitted by the IUPAC code at
+/// the same position of the 4-bit sequence.
+///
+/// A gap is compatible with nothing and an `N` is compatible with everything.
+///
+/// # Arguments
+///
+/// * `iupac` - The 4-bit encoded IUPAC sequence, e.g. a primer or probe.
+/// * `ebuf` - The 2-bit encoded sequence to match against it.
+/// * `n_bases` - The number of bases to compare.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidLength` if either buffer holds fewer than `n_bases` bases.
+pub fn is_compatible(iupac: &[u64], ebuf: &[u64], n_bases: usize) -> Result<bool, NucleotideError> {
+    if iupac.len() < n_bases.div_ceil(16) || ebuf.len() < n_bases.div_ceil(32) {
+        return Err(NucleotideError::InvalidLength(n_bases));
+    }
+
+    for (i, &word) in iupac.iter().take(n_bases.div_ceil(16)).enumerate() {
+        let codes = (ebuf[i / 2] >> ((i % 2) * 32)) as u32;
+        let expected = NIBBLE_LOW & nibble_mask((n_bases - i * 16).min(16));
+        if naive::compatible_nibbles(word, codes) & expected != expected {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::encode_alloc as encode_2bit;
+
+    fn random_iupac(len: usize, alphabet: &[u8]) -> Vec<u8> {
+        let mut state = 0x2545F4914F6CDD1Du64;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                alphabet[(state % alphabet.len() as u64) as usize]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_encode_decode_roundtrip() {
+        let seq = random_iupac(1000, b"ACGTRYSWKMBDHVN-acgtrysWKMbdhvn");
+        for len in [0, 1, 15, 16, 17, 31, 32, 33, 100, 1000] {
+            let ebuf = encode_alloc(&seq[..len]).unwrap();
+            assert_eq!(ebuf.len(), len.div_ceil(16));
+            let mut dbuf = Vec::new();
+            decode(&ebuf, len, &mut dbuf).unwrap();
+            assert_eq!(dbuf, seq[..len].to_ascii_uppercase());
+        }
+    }
+
+    #[test]
+    fn test_encode_invalid() {
+        let mut seq = vec![b'A'; 100];
+        seq[70] = b'X';
+        assert_eq!(
+            encode_alloc(&seq),
+            Err(NucleotideError::InvalidBase {
+                base: b'X',
+                position: 70
+            })
+        );
+        assert_eq!(
+            as_4bit(&seq[..17]),
+            Err(NucleotideError::SequenceTooLong(17))
+        );
+    }
+
+    #[test]
+    fn test_decode_too_short() {
+        let ebuf = encode_alloc(b"ACGT").unwrap();
+        assert_eq!(
+            decode(&ebuf, 17, &mut Vec::new()),
+            Err(NucleotideError::InvalidLength(17))
+        );
+    }
+
+    #[test]
+    fn test_2bit_conversion_roundtrip() {
+        let seq = random_iupac(1000, b"ACGTacgt");
+        for len in [1, 15, 16, 17, 31, 32, 33, 48, 100, 1000] {
+            let two_bit = encode_2bit(&seq[..len]).unwrap();
+            let four_bit = encode_alloc(&seq[..len]).unwrap();
+
+            let mut converted = Vec::new();
+            from_2bit(&two_bit, len, &mut converted).unwrap();
+            assert_eq!(converted, four_bit);
+
+            to_2bit(&four_bit, len, &mut converted).unwrap();
+            assert_eq!(converted, two_bit);
+        }
+    }
+
+    #[test]
+    fn test_to_2bit_ambiguous() {
+        let mut seq = vec![b'C'; 40];
+        seq[35] = b'-';
+        let ebuf = encode_alloc(&seq).unwrap();
+        let mut out = Vec::new();
+        assert_eq!(
+            to_2bit(&ebuf, 40, &mut out),
+            Err(NucleotideError::InvalidBase {
+                base: b'-',
+                position: 35
+            })
+        );
+        // The ambiguous base is outside of the converted range
+        to_2bit(&ebuf, 35, &mut out).unwrap();
+        assert_eq!(out, encode_2bit(&seq[..35]).unwrap());
+    }
+
+    #[test]
+    fn test_is_compatible() {
+        let probe = random_iupac(100, b"ACGTRYSWKMBDHVN");
+        let iupac = encode_alloc(&probe).unwrap();
+
+        // Expand every ambiguity code to one of the bases it admits
+        let mut target: Vec<u8> = probe
+            .iter()
+            .map(|&b| match b {
+                b'R' | b'M' | b'W' | b'D' | b'H' | b'V' | b'N' => b'A',
+                b'Y' | b'S' | b'B' => b'C',
+                b'K' => b'G',
+                b => b,
+            })
+            .collect();
+        assert!(is_compatible(&iupac, &encode_2bit(&target).unwrap(), 100).unwrap());
+
+        // Replace a concrete base with one it does not admit
+        let pos = probe.iter().position(|&b| b == b'G').unwrap();
+        target[pos] = b'T';
+        let ebuf = encode_2bit(&target).unwrap();
+        assert!(!is_compatible(&iupac, &ebuf, 100).unwrap());
+        assert!(is_compatible(&iupac, &ebuf, pos).unwrap());
+    }
+
+    #[test]
+    fn test_gap_is_incompatible() {
+        let iupac = encode_alloc(b"AN-").unwrap();
+        for base in [b'A', b'C', b'G', b'T'] {
+            let ebuf = encode_2bit(&[b'A', base, base]).unwrap();
+            assert!(is_compatible(&iupac, &ebuf, 2).unwrap());
+            assert!(!is_compatible(&iupac, &ebuf, 3).unwrap());
+        }
+    }
+}
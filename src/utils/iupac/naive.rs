@@ -0,0 +1,185 @@
+use crate::NucleotideError;
+
+/// Sentinel in [`ENCODE_LOOKUP`] for bytes that are not IUPAC codes.
+pub const INVALID_CODE: u8 = 0xFF;
+
+/// Maps each 4-bit code back to its IUPAC character.
+pub const DECODE_LOOKUP: [u8; 16] = *b"-ACMGRSVTWYHKDBN";
+
+/// Maps ASCII bytes to 4-bit codes (case insensitive), or [`INVALID_CODE`].
+pub const ENCODE_LOOKUP: [u8; 256] = {
+    let mut table = [INVALID_CODE; 256];
+    let mut code = 0;
+    while code < 16 {
+        let base = DECODE_LOOKUP[code];
+        table[base as usize] = code as u8;
+        table[base.to_ascii_lowercase() as usize] = code as u8;
+        code += 1;
+    }
+    table
+};
+
+/// Selects the lowest bit of every nibble
+pub const NIBBLE_LOW: u64 = 0x1111_1111_1111_1111;
+
+/// Selects every nibble holding one of the first `n_bases` bases of a word.
+#[inline(always)]
+pub fn nibble_mask(n_bases: usize) -> u64 {
+    if n_bases >= 16 {
+        u64::MAX
+    } else {
+        (1u64 << (n_bases * 4)) - 1
+    }
+}
+
+#[inline(always)]
+pub fn as_4bit(seq: &[u8]) -> Result<u64, NucleotideError> {
+    if seq.len() > 16 {
+        return Err(NucleotideError::SequenceTooLong(seq.len()));
+    }
+    let mut packed = 0u64;
+    for (i, &base) in seq.iter().enumerate() {
+        let code = ENCODE_LOOKUP[base as usize];
+        if code == INVALID_CODE {
+            return Err(NucleotideError::InvalidBase { base, position: i });
+        }
+        packed |= (code as u64) << (i * 4);
+    }
+    Ok(packed)
+}
+
+pub fn from_4bit(
+    packed: u64,
+    expected_size: usize,
+    sequence: &mut Vec<u8>,
+) -> Result<(), NucleotideError> {
+    if expected_size > 16 {
+        return Err(NucleotideError::InvalidLength(expected_size));
+    }
+    sequence
+        .extend((0..expected_size).map(|i| DECODE_LOOKUP[((packed >> (i * 4)) & 0xF) as usize]));
+    Ok(())
+}
+
+pub fn encode_internal(sequence: &[u8], ebuf: &mut Vec<u64>) -> Result<(), NucleotideError> {
+    ebuf.clear();
+    for (i, chunk) in sequence.chunks(16).enumerate() {
+        ebuf.push(as_4bit(chunk).map_err(|err| err.offset_position(i * 16))?);
+    }
+    Ok(())
+}
+
+pub fn decode_internal(
+    ebuf: &[u64],
+    n_bases: usize,
+    dbuf: &mut Vec<u8>,
+) -> Result<(), NucleotideError> {
+    let n_chunks = n_bases.div_ceil(16);
+    if ebuf.len() < n_chunks {
+        return Err(NucleotideError::InvalidLength(n_bases));
+    }
+    dbuf.reserve(n_bases);
+    for (i, &packed) in ebuf.iter().take(n_chunks).enumerate() {
+        from_4bit(packed, (n_bases - i * 16).min(16), dbuf)?;
+    }
+    Ok(())
+}
+
+/// Spreads the 16 2-bit codes of `codes` into the low two bits of 16 nibbles.
+#[inline(always)]
+pub fn spread_2bit(codes: u32) -> u64 {
+    let mut x = codes as u64;
+    x = (x | (x << 16)) & 0x0000_FFFF_0000_FFFF;
+    x = (x | (x << 8)) & 0x00FF_00FF_00FF_00FF;
+    x = (x | (x << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+    (x | (x << 2)) & 0x3333_3333_3333_3333
+}
+
+/// Gathers the low two bits of 16 nibbles into 16 2-bit codes.
+#[inline(always)]
+pub fn compress_2bit(nibbles: u64) -> u32 {
+    let mut x = nibbles & 0x3333_3333_3333_3333;
+    x = (x | (x >> 2)) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | (x >> 4)) & 0x00FF_00FF_00FF_00FF;
+    x = (x | (x >> 8)) & 0x0000_FFFF_0000_FFFF;
+    (x | (x >> 16)) as u32
+}
+
+/// Converts 16 2-bit codes into their one-hot 4-bit codes.
+#[inline(always)]
+pub fn onehot_from_2bit(codes: u32) -> u64 {
+    let x = spread_2bit(codes);
+    let lo = x & NIBBLE_LOW;
+    let hi = (x >> 1) & NIBBLE_LOW;
+    (!hi & !lo & NIBBLE_LOW) | ((!hi & lo) << 1) | ((hi & !lo) << 2) | ((hi & lo) << 3)
+}
+
+/// Converts 16 one-hot 4-bit codes into 2-bit codes.
+///
+/// Returns the index of the first nibble within `n_bases` that is not exactly one
+/// base (an ambiguity code or a gap) as the error.
+#[inline(always)]
+pub fn onehot_to_2bit(packed: u64, n_bases: usize) -> Result<u32, usize> {
+    let packed = packed & nibble_mask(n_bases);
+    let b0 = packed & NIBBLE_LOW;
+    let b1 = (packed >> 1) & NIBBLE_LOW;
+    let b2 = (packed >> 2) & NIBBLE_LOW;
+    let b3 = (packed >> 3) & NIBBLE_LOW;
+    let any = b0 | b1 | b2 | b3;
+    let pair = (b0 & (b1 | b2 | b3)) | (b1 & (b2 | b3)) | (b2 & b3);
+    let valid = NIBBLE_LOW & nibble_mask(n_bases);
+    let single = any & !pair & valid;
+    if single != valid {
+        return Err(((single ^ valid).trailing_zeros() / 4) as usize);
+    }
+    Ok(compress_2bit((b1 | b3) | ((b2 | b3) << 1)))
+}
+
+/// Returns a mask with the lowest bit set for every nibble of `iupac` that admits
+/// the corresponding base of `codes`.
+#[inline(always)]
+pub fn compatible_nibbles(iupac: u64, codes: u32) -> u64 {
+    let hits = iupac & onehot_from_2bit(codes);
+    (hits | (hits >> 1) | (hits >> 2) | (hits >> 3)) & NIBBLE_LOW
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_tables() {
+        for (code, &base) in DECODE_LOOKUP.iter().enumerate() {
+            assert_eq!(ENCODE_LOOKUP[base as usize], code as u8);
+        }
+        assert_eq!(ENCODE_LOOKUP[b'r' as usize], 0b0101);
+        assert_eq!(ENCODE_LOOKUP[b'X' as usize], INVALID_CODE);
+        assert_eq!(ENCODE_LOOKUP[b'U' as usize], INVALID_CODE);
+    }
+
+    #[test]
+    fn test_spread_compress() {
+        for codes in [0u32, 0xE4, 0xDEAD_BEEF, u32::MAX] {
+            assert_eq!(compress_2bit(spread_2bit(codes)), codes);
+        }
+    }
+
+    #[test]
+    fn test_onehot_roundtrip() {
+        let codes = 0x1B1B_E4E4;
+        let onehot = onehot_from_2bit(codes);
+        for i in 0..16 {
+            let code = (codes >> (i * 2)) & 0b11;
+            assert_eq!((onehot >> (i * 4)) & 0xF, 1 << code);
+        }
+        assert_eq!(onehot_to_2bit(onehot, 16), Ok(codes));
+    }
+
+    #[test]
+    fn test_onehot_to_2bit_ambiguous() {
+        let packed = as_4bit(b"ACGTNA").unwrap();
+        assert_eq!(onehot_to_2bit(packed, 4), Ok(0b11100100));
+        assert_eq!(onehot_to_2bit(packed, 6), Err(4));
+        assert_eq!(onehot_to_2bit(as_4bit(b"A-").unwrap(), 2), Err(1));
+    }
+}
@@ -0,0 +1,96 @@
+use super::naive;
+use crate::NucleotideError;
+use std::arch::x86_64::*;
+
+/// Converts 16 ASCII bytes into 4-bit codes, returning the codes and a validity mask.
+///
+/// SSE2 has no byte shuffle, so each IUPAC letter is matched with its own comparison.
+#[inline(always)]
+unsafe fn codes_16(chunk: __m128i) -> (__m128i, i32) {
+    let lower = _mm_or_si128(chunk, _mm_set1_epi8(0x20));
+
+    // Gaps are matched on the raw bytes so that control characters are not folded onto '-'
+    let mut matched = _mm_cmpeq_epi8(chunk, _mm_set1_epi8(b'-' as i8));
+    let mut codes = _mm_setzero_si128();
+    for (code, &base) in naive::DECODE_LOOKUP.iter().enumerate().skip(1) {
+        let hit = _mm_cmpeq_epi8(lower, _mm_set1_epi8(base.to_ascii_lowercase() as i8));
+        codes = _mm_or_si128(codes, _mm_and_si128(hit, _mm_set1_epi8(code as i8)));
+        matched = _mm_or_si128(matched, hit);
+    }
+    (codes, !_mm_movemask_epi8(matched) & 0xFFFF)
+}
+
+/// Packs 16 code bytes into a word of 16 nibbles.
+#[inline(always)]
+unsafe fn pack_16(codes: __m128i) -> u64 {
+    // Merge each odd code into the high nibble of its even neighbour
+    let pairs = _mm_or_si128(
+        _mm_and_si128(codes, _mm_set1_epi16(0x00FF)),
+        _mm_srli_epi16(codes, 4),
+    );
+    _mm_cvtsi128_si64(_mm_packus_epi16(pairs, pairs)) as u64
+}
+
+pub fn encode_internal(sequence: &[u8], ebuf: &mut Vec<u64>) -> Result<(), NucleotideError> {
+    ebuf.clear();
+    ebuf.reserve(sequence.len().div_ceil(16));
+
+    let simd_len = sequence.len() - (sequence.len() % 16);
+    unsafe {
+        for chunk_idx in (0..simd_len).step_by(16) {
+            let chunk = _mm_loadu_si128(sequence[chunk_idx..].as_ptr() as *const __m128i);
+            let (codes, invalid) = codes_16(chunk);
+            if invalid != 0 {
+                let position = chunk_idx + invalid.trailing_zeros() as usize;
+                return Err(NucleotideError::InvalidBase {
+                    base: sequence[position],
+                    position,
+                });
+            }
+            ebuf.push(pack_16(codes));
+        }
+    }
+
+    if simd_len < sequence.len() {
+        let bits =
+            naive::as_4bit(&sequence[simd_len..]).map_err(|err| err.offset_position(simd_len))?;
+        ebuf.push(bits);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sse_matches_naive() {
+        let alphabet = b"ACGTRYSWKMBDHVN-acgtrysw";
+        let seq: Vec<u8> = (0..517)
+            .map(|i| alphabet[(i * 7) % alphabet.len()])
+            .collect();
+        for len in [0, 15, 16, 17, 32, 100, 517] {
+            let mut expected = Vec::new();
+            naive::encode_internal(&seq[..len], &mut expected).unwrap();
+            let mut ebuf = Vec::new();
+            encode_internal(&seq[..len], &mut ebuf).unwrap();
+            assert_eq!(ebuf, expected);
+        }
+    }
+
+    #[test]
+    fn test_sse_invalid_base() {
+        let mut seq = vec![b'N'; 32];
+        for invalid in [b'X', b'\r', b'@', b'U', 0xE1] {
+            seq[21] = invalid;
+            let mut ebuf = Vec::new();
+            assert_eq!(
+                encode_internal(&seq, &mut ebuf),
+                Err(NucleotideError::InvalidBase {
+                    base: invalid,
+                    position: 21
+                })
+            );
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use std::iter::FusedIterator;
+
+use super::{CanonicalKmerIter, KmerIterator, KmerPolicy, BASE_LOOKUP, INVALID_BASE};
+use crate::NucleotideError;
+
+/// Iterates over the 2-bit packed k-mers of a nucleotide sequence.
+///
+/// Each k-mer is packed in the same way as `as_2bit`, but is computed by shifting
+/// a single new base into the previous k-mer rather than re-encoding the whole window.
+///
+/// The iterator yields `(position, kmer)` pairs where `position` is the offset of the
+/// first base of the k-mer in the input sequence.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{as_2bit, KmerIter, KmerPolicy};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let kmers: Vec<_> = KmerIter::new(b"ACGTA", 4, KmerPolicy::Error)?.collect();
+/// assert_eq!(kmers, vec![(0, as_2bit(b"ACGT")?), (1, as_2bit(b"CGTA")?)]);
+///
+/// // Skip the k-mers overlapping the invalid base
+/// let kmers: Vec<_> = KmerIter::new(b"ACGNACGT", 3, KmerPolicy::Skip)?.collect();
+/// assert_eq!(kmers, vec![(0, as_2bit(b"ACG")?), (4, as_2bit(b"ACG")?), (5, as_2bit(b"CGT")?)]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct KmerIter<'a> {
+    seq: &'a [u8],
+    k: usize,
+    /// Bit offset of the last base of the k-mer
+    shift: usize,
+    /// Index of the next base to read
+    pos: usize,
+    /// Number of consecutive valid bases read
+    filled: usize,
+    kmer: u64,
+}
+
+impl<'a> KmerIter<'a> {
+    /// Creates a new k-mer iterator over a nucleotide sequence.
+    ///
+    /// # Arguments
+    ///
+    /// * `seq` - A byte slice containing ASCII nucleotides (A,C,G,T, case insensitive)
+    /// * `k` - The k-mer size (1 to 32)
+    /// * `policy` - How to handle invalid bases
+    ///
+    /// # Errors
+    ///
+    /// Returns `NucleotideError::InvalidLength` if `k` is zero or greater than 32.
+    ///
+    /// Returns `NucleotideError::InvalidBase` if the policy is `KmerPolicy::Error`
+    /// and the sequence contains any characters other than A,C,G,T (case insensitive).
+    pub fn new(seq: &'a [u8], k: usize, policy: KmerPolicy) -> Result<Self, NucleotideError> {
+        if k == 0 || k > 32 {
+            return Err(NucleotideError::InvalidLength(k));
+        }
+        if policy == KmerPolicy::Error {
+            if let Some(position) = seq
+                .iter()
+                .position(|&b| BASE_LOOKUP[b as usize] == INVALID_BASE)
+            {
+                return Err(NucleotideError::InvalidBase {
+                    base: seq[position],
+                    position,
+                });
+            }
+        }
+        Ok(Self {
+            seq,
+            k,
+            shift: (k - 1) * 2,
+            pos: 0,
+            filled: 0,
+            kmer: 0,
+        })
+    }
+
+    /// Returns the k-mer size.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Converts the iterator into one over canonical k-mers.
+    ///
+    /// See [`CanonicalKmerIter`] for details.
+    pub fn canonical(self) -> CanonicalKmerIter<Self> {
+        let k = self.k;
+        CanonicalKmerIter::new(self, k)
+    }
+}
+
+impl Iterator for KmerIter<'_> {
+    type Item = (usize, u64);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&base) = self.seq.get(self.pos) {
+            self.pos += 1;
+
+            let bits = BASE_LOOKUP[base as usize];
+            if bits == INVALID_BASE {
+                // Restart the k-mer after the invalid base
+                self.filled = 0;
+                continue;
+            }
+
+            self.kmer = (self.kmer >> 2) | ((bits as u64) << self.shift);
+            self.filled += 1;
+            if self.filled >= self.k {
+                return Some((self.pos - self.k, self.kmer));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.seq.len() - self.pos;
+        let needed = self.k - self.filled.min(self.k - 1);
+        (0, Some((remaining + 1).saturating_sub(needed)))
+    }
+}
+
+impl FusedIterator for KmerIter<'_> {}
+
+impl KmerIterator for KmerIter<'_> {
+    fn k(&self) -> usize {
+        self.k
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::as_2bit;
+    use nucgen::Sequence;
+
+    #[test]
+    fn test_kmers_match_as_2bit() {
+        let mut rng = rand::thread_rng();
+        let mut seq = Sequence::new();
+        seq.fill_buffer(&mut rng, 200);
+
+        for k in 1..=32 {
+            let observed: Vec<_> = KmerIter::new(seq.bytes(), k, KmerPolicy::Error)
+                .unwrap()
+                .collect();
+            let expected: Vec<_> = seq
+                .bytes()
+                .windows(k)
+                .enumerate()
+                .map(|(i, window)| (i, as_2bit(window).unwrap()))
+                .collect();
+            assert_eq!(observed, expected, "Failed for k = {}", k);
+        }
+    }
+
+    #[test]
+    fn test_kmers_short_sequence() {
+        assert_eq!(
+            KmerIter::new(b"ACG", 4, KmerPolicy::Error).unwrap().count(),
+            0
+        );
+        assert_eq!(KmerIter::new(b"", 4, KmerPolicy::Error).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_kmers_skip_invalid() {
+        let seq = b"ACGTNACGTANNCGTAC";
+        let observed: Vec<_> = KmerIter::new(seq, 4, KmerPolicy::Skip).unwrap().collect();
+        let expected = vec![
+            (0, as_2bit(b"ACGT").unwrap()),
+            (5, as_2bit(b"ACGT").unwrap()),
+            (6, as_2bit(b"CGTA").unwrap()),
+            (12, as_2bit(b"CGTA").unwrap()),
+            (13, as_2bit(b"GTAC").unwrap()),
+        ];
+        assert_eq!(observed, expected);
+    }
+
+    #[test]
+    fn test_kmers_error_invalid() {
+        assert!(matches!(
+            KmerIter::new(b"ACGTNACGT", 4, KmerPolicy::Error),
+            Err(NucleotideError::InvalidBase {
+                base: b'N',
+                position: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn test_kmers_invalid_k() {
+        assert!(matches!(
+            KmerIter::new(b"ACGT", 0, KmerPolicy::Error),
+            Err(NucleotideError::InvalidLength(0))
+        ));
+        assert!(matches!(
+            KmerIter::new(b"ACGT", 33, KmerPolicy::Error),
+            Err(NucleotideError::InvalidLength(33))
+        ));
+    }
+
+    #[test]
+    fn test_kmers_size_hint() {
+        let iter = KmerIter::new(b"ACGTACGT", 4, KmerPolicy::Error).unwrap();
+        assert_eq!(iter.size_hint(), (0, Some(5)));
+        assert_eq!(iter.count(), 5);
+    }
+}
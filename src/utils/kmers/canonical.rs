@@ -0,0 +1,177 @@
+use std::iter::FusedIterator;
+
+use super::Strand;
+use crate::revcomp_2bit;
+
+/// Iterates over the canonical k-mers of a sequence.
+///
+/// Wraps a k-mer iterator and tracks the reverse complement of each k-mer alongside it,
+/// updating it with the single new base on every step. The canonical k-mer is the smaller
+/// of the two, and ties (palindromic k-mers) are reported on the forward strand.
+///
+/// The iterator yields `(position, kmer, strand)` tuples where `strand` is the strand the
+/// canonical k-mer was taken from.
+///
+/// Created with [`KmerIter::canonical`](crate::KmerIter::canonical) or
+/// [`PackedKmerIter::canonical`](crate::PackedKmerIter::canonical).
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{as_2bit, KmerIter, KmerPolicy, Strand};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let kmers: Vec<_> = KmerIter::new(b"TTGCA", 3, KmerPolicy::Error)?
+///     .canonical()
+///     .collect();
+/// assert_eq!(
+///     kmers,
+///     vec![
+///         (0, as_2bit(b"CAA")?, Strand::Reverse),
+///         (1, as_2bit(b"GCA")?, Strand::Reverse),
+///         (2, as_2bit(b"GCA")?, Strand::Forward),
+///     ]
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CanonicalKmerIter<I> {
+    inner: I,
+    k: usize,
+    mask: u64,
+    /// Bit offset of the last base of the k-mer
+    shift: usize,
+    /// Position the next k-mer must have to extend the current reverse complement
+    next_pos: Option<usize>,
+    rc: u64,
+}
+
+impl<I> CanonicalKmerIter<I> {
+    pub(crate) fn new(inner: I, k: usize) -> Self {
+        let mask = if k == 32 {
+            u64::MAX
+        } else {
+            (1u64 << (k * 2)) - 1
+        };
+        Self {
+            inner,
+            k,
+            mask,
+            shift: (k - 1) * 2,
+            next_pos: None,
+            rc: 0,
+        }
+    }
+
+    /// Returns the k-mer size.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+}
+
+impl<I: Iterator<Item = (usize, u64)>> Iterator for CanonicalKmerIter<I> {
+    type Item = (usize, u64, Strand);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (pos, fwd) = self.inner.next()?;
+
+        self.rc = if self.next_pos == Some(pos) {
+            // The new last base of the forward k-mer is complemented into the
+            // first base of the reverse complement
+            let bits = fwd >> self.shift;
+            ((self.rc << 2) | (bits ^ 0b11)) & self.mask
+        } else {
+            // Restarted after a gap in the sequence
+            revcomp_2bit(fwd, self.k)
+        };
+        self.next_pos = Some(pos + 1);
+
+        if self.rc < fwd {
+            Some((pos, self.rc, Strand::Reverse))
+        } else {
+            Some((pos, fwd, Strand::Forward))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<I: ExactSizeIterator<Item = (usize, u64)>> ExactSizeIterator for CanonicalKmerIter<I> {}
+
+impl<I: FusedIterator<Item = (usize, u64)>> FusedIterator for CanonicalKmerIter<I> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{canonical_2bit, encode_alloc, KmerIter, KmerPolicy, PackedKmerIter};
+    use nucgen::Sequence;
+
+    fn expected_canonical(kmers: &[(usize, u64)], k: usize) -> Vec<(usize, u64, Strand)> {
+        kmers
+            .iter()
+            .map(|&(pos, kmer)| {
+                let canonical = canonical_2bit(kmer, k);
+                let strand = if canonical == kmer {
+                    Strand::Forward
+                } else {
+                    Strand::Reverse
+                };
+                (pos, canonical, strand)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_canonical_matches_canonical_2bit() {
+        let mut rng = rand::thread_rng();
+        let mut seq = Sequence::new();
+        seq.fill_buffer(&mut rng, 150);
+        let ebuf = encode_alloc(seq.bytes()).unwrap();
+
+        for k in 1..=32 {
+            let kmers: Vec<_> = KmerIter::new(seq.bytes(), k, KmerPolicy::Error)
+                .unwrap()
+                .collect();
+            let expected = expected_canonical(&kmers, k);
+
+            let observed: Vec<_> = KmerIter::new(seq.bytes(), k, KmerPolicy::Error)
+                .unwrap()
+                .canonical()
+                .collect();
+            assert_eq!(observed, expected, "Failed for k = {}", k);
+
+            let observed: Vec<_> = PackedKmerIter::new(&ebuf, seq.bytes().len(), k)
+                .unwrap()
+                .canonical()
+                .collect();
+            assert_eq!(observed, expected, "Failed for packed k = {}", k);
+        }
+    }
+
+    #[test]
+    fn test_canonical_restarts_after_skip() {
+        let seq = b"ACGGTTNNCATGCATNGGGACCA";
+        let kmers: Vec<_> = KmerIter::new(seq, 5, KmerPolicy::Skip).unwrap().collect();
+        let observed: Vec<_> = KmerIter::new(seq, 5, KmerPolicy::Skip)
+            .unwrap()
+            .canonical()
+            .collect();
+        assert_eq!(observed, expected_canonical(&kmers, 5));
+    }
+
+    #[test]
+    fn test_canonical_palindrome_is_forward() {
+        let observed: Vec<_> = KmerIter::new(b"ACGT", 4, KmerPolicy::Error)
+            .unwrap()
+            .canonical()
+            .collect();
+        assert_eq!(
+            observed,
+            vec![(0, crate::as_2bit(b"ACGT").unwrap(), Strand::Forward)]
+        );
+    }
+}
@@ -0,0 +1,75 @@
+mod bytes;
+mod canonical;
+mod packed;
+
+pub use bytes::KmerIter;
+pub use canonical::CanonicalKmerIter;
+pub use packed::PackedKmerIter;
+
+/// The strand a k-mer was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Strand {
+    /// The k-mer as it appears in the sequence
+    Forward,
+    /// The reverse complement of the k-mer
+    Reverse,
+}
+
+/// Determines how k-mer iterators handle bases other than A, C, G, T.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KmerPolicy {
+    /// Fail with `NucleotideError::InvalidBase` if the sequence contains any invalid base.
+    #[default]
+    Error,
+    /// Skip every k-mer overlapping an invalid base and restart after it.
+    Skip,
+}
+
+/// An iterator over the forward k-mers of a sequence.
+///
+/// Implemented by [`KmerIter`] and [`PackedKmerIter`] so that consumers such as
+/// [`SyncmerIter`](crate::SyncmerIter) can work over both raw bytes and encoded buffers.
+pub trait KmerIterator: Iterator<Item = (usize, u64)> {
+    /// Returns the k-mer size.
+    fn k(&self) -> usize;
+}
+
+/// A k-mer yielded by one of the k-mer iterators.
+///
+/// Allows consumers such as [`MinimizerIter`](crate::MinimizerIter) to accept both
+/// forward `(position, kmer)` and canonical `(position, kmer, strand)` items.
+pub trait KmerItem {
+    /// Splits the item into its position, packed k-mer and strand.
+    fn into_parts(self) -> (usize, u64, Strand);
+}
+
+impl KmerItem for (usize, u64) {
+    #[inline(always)]
+    fn into_parts(self) -> (usize, u64, Strand) {
+        (self.0, self.1, Strand::Forward)
+    }
+}
+
+impl KmerItem for (usize, u64, Strand) {
+    #[inline(always)]
+    fn into_parts(self) -> (usize, u64, Strand) {
+        self
+    }
+}
+
+/// Sentinel in [`BASE_LOOKUP`] for bytes that are not valid nucleotides.
+pub(crate) const INVALID_BASE: u8 = 0xFF;
+
+/// Lookup table from ASCII (case insensitive) to 2-bit nucleotide codes.
+pub(crate) const BASE_LOOKUP: [u8; 256] = {
+    let mut table = [INVALID_BASE; 256];
+    table[b'A' as usize] = 0b00;
+    table[b'a' as usize] = 0b00;
+    table[b'C' as usize] = 0b01;
+    table[b'c' as usize] = 0b01;
+    table[b'G' as usize] = 0b10;
+    table[b'g' as usize] = 0b10;
+    table[b'T' as usize] = 0b11;
+    table[b't' as usize] = 0b11;
+    table
+};
@@ -0,0 +1,180 @@
+use std::iter::FusedIterator;
+
+use super::{CanonicalKmerIter, KmerIterator};
+use crate::NucleotideError;
+
+/// Iterates over the k-mers of a 2-bit packed sequence without decoding it.
+///
+/// The sequence is expected to be packed as by `encode`, with 32 bases per u64
+/// and the final u64 holding the remainder. K-mers spanning two u64s are stitched
+/// together and packed in the same way as `as_2bit`.
+///
+/// The iterator yields `(position, kmer)` pairs where `position` is the offset of the
+/// first base of the k-mer in the sequence.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{as_2bit, encode_alloc, PackedKmerIter};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let seq = b"ACGTACGTACGTACGTACGTACGTACGTACGTTTGCA";
+/// let ebuf = encode_alloc(seq)?;
+///
+/// let kmers: Vec<_> = PackedKmerIter::new(&ebuf, seq.len(), 5)?.collect();
+/// assert_eq!(kmers.len(), seq.len() - 4);
+/// assert_eq!(kmers[30], (30, as_2bit(b"GTTTG")?));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PackedKmerIter<'a> {
+    ebuf: &'a [u64],
+    n_bases: usize,
+    k: usize,
+    mask: u64,
+    /// Position of the next k-mer
+    pos: usize,
+}
+
+impl<'a> PackedKmerIter<'a> {
+    /// Creates a new k-mer iterator over a 2-bit packed sequence.
+    ///
+    /// # Arguments
+    ///
+    /// * `ebuf` - The encoded sequence buffer
+    /// * `n_bases` - The total length of the sequence in bases
+    /// * `k` - The k-mer size (1 to 32)
+    ///
+    /// # Errors
+    ///
+    /// Returns `NucleotideError::InvalidLength` if `k` is zero or greater than 32,
+    /// or if `ebuf` is too short to hold `n_bases`.
+    pub fn new(ebuf: &'a [u64], n_bases: usize, k: usize) -> Result<Self, NucleotideError> {
+        if k == 0 || k > 32 {
+            return Err(NucleotideError::InvalidLength(k));
+        }
+        if ebuf.len() < n_bases.div_ceil(32) {
+            return Err(NucleotideError::InvalidLength(n_bases));
+        }
+        let mask = if k == 32 {
+            u64::MAX
+        } else {
+            (1u64 << (k * 2)) - 1
+        };
+        Ok(Self {
+            ebuf,
+            n_bases,
+            k,
+            mask,
+            pos: 0,
+        })
+    }
+
+    /// Returns the k-mer size.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Converts the iterator into one over canonical k-mers.
+    ///
+    /// See [`CanonicalKmerIter`] for details.
+    pub fn canonical(self) -> CanonicalKmerIter<Self> {
+        let k = self.k;
+        CanonicalKmerIter::new(self, k)
+    }
+}
+
+impl Iterator for PackedKmerIter<'_> {
+    type Item = (usize, u64);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos + self.k > self.n_bases {
+            return None;
+        }
+
+        let chunk_idx = self.pos / 32; // Which u64 contains the first base
+        let bit_idx = (self.pos % 32) * 2; // Which bit position within that u64
+
+        let mut kmer = self.ebuf[chunk_idx] >> bit_idx;
+        if bit_idx + self.k * 2 > 64 {
+            // Stitch in the bases from the next u64
+            kmer |= self.ebuf[chunk_idx + 1] << (64 - bit_idx);
+        }
+
+        let pos = self.pos;
+        self.pos += 1;
+        Some((pos, kmer & self.mask))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.n_bases + 1).saturating_sub(self.pos + self.k);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for PackedKmerIter<'_> {}
+
+impl FusedIterator for PackedKmerIter<'_> {}
+
+impl KmerIterator for PackedKmerIter<'_> {
+    fn k(&self) -> usize {
+        self.k
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{encode_alloc, KmerIter, KmerPolicy};
+    use nucgen::Sequence;
+
+    #[test]
+    fn test_packed_kmers_match_bytes() {
+        let mut rng = rand::thread_rng();
+        let mut seq = Sequence::new();
+
+        for len in [1, 31, 32, 33, 63, 64, 65, 150, 301] {
+            seq.fill_buffer(&mut rng, len);
+            let ebuf = encode_alloc(seq.bytes()).unwrap();
+
+            for k in 1..=32 {
+                let observed: Vec<_> = PackedKmerIter::new(&ebuf, len, k).unwrap().collect();
+                let expected: Vec<_> = KmerIter::new(seq.bytes(), k, KmerPolicy::Error)
+                    .unwrap()
+                    .collect();
+                assert_eq!(observed, expected, "Failed for len = {}, k = {}", len, k);
+            }
+        }
+    }
+
+    #[test]
+    fn test_packed_kmers_exact_size() {
+        let ebuf = encode_alloc(b"ACGTACGTAC").unwrap();
+        let mut iter = PackedKmerIter::new(&ebuf, 10, 4).unwrap();
+        assert_eq!(iter.len(), 7);
+        iter.next();
+        assert_eq!(iter.len(), 6);
+
+        let iter = PackedKmerIter::new(&ebuf, 10, 11).unwrap();
+        assert_eq!(iter.len(), 0);
+    }
+
+    #[test]
+    fn test_packed_kmers_invalid_inputs() {
+        let ebuf = encode_alloc(b"ACGT").unwrap();
+        assert!(matches!(
+            PackedKmerIter::new(&ebuf, 4, 0),
+            Err(NucleotideError::InvalidLength(0))
+        ));
+        assert!(matches!(
+            PackedKmerIter::new(&ebuf, 4, 33),
+            Err(NucleotideError::InvalidLength(33))
+        ));
+        assert!(matches!(
+            PackedKmerIter::new(&ebuf, 33, 4),
+            Err(NucleotideError::InvalidLength(33))
+        ));
+    }
+}
@@ -0,0 +1,400 @@
+use std::ops::Range;
+
+use crate::utils::{decode, encode, encode_append};
+use crate::NucleotideError;
+
+/// Checks that every run is a valid range within the first `n_bases` bases.
+fn check_runs(runs: &[Range<usize>], n_bases: usize) -> Result<(), NucleotideError> {
+    match runs
+        .iter()
+        .find(|run| run.start > run.end || run.end > n_bases)
+    {
+        Some(run) => Err(NucleotideError::InvalidRange {
+            start: run.start,
+            end: run.end,
+            length: n_bases,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Returns true if the byte is an ambiguous `N` base.
+#[inline(always)]
+pub(crate) fn is_n(base: u8) -> bool {
+    base == b'N' || base == b'n'
+}
+
+/// Returns true if the byte is a soft-masked (lowercase) base.
+#[inline(always)]
+pub(crate) fn is_soft_masked(base: u8) -> bool {
+    base.is_ascii_lowercase()
+}
+
+/// Appends the maximal runs of positions matching `pred` to `runs`.
+pub(crate) fn find_runs(seq: &[u8], pred: impl Fn(u8) -> bool, runs: &mut Vec<Range<usize>>) {
+    let mut start = None;
+    for (i, &base) in seq.iter().enumerate() {
+        match (pred(base), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                runs.push(s..i);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        runs.push(s..seq.len());
+    }
+}
+
+/// Returns true if `pos` falls within one of the sorted, non-overlapping `runs`.
+#[inline]
+pub(crate) fn runs_contain(runs: &[Range<usize>], pos: usize) -> bool {
+    let idx = runs.partition_point(|run| run.end <= pos);
+    runs.get(idx).is_some_and(|run| run.start <= pos)
+}
+
+/// Returns the runs overlapping `range`, clipped to it and shifted to start at zero.
+pub(crate) fn clip_runs(
+    runs: &[Range<usize>],
+    range: Range<usize>,
+) -> impl Iterator<Item = Range<usize>> + '_ {
+    let first = runs.partition_point(|run| run.end <= range.start);
+    runs[first..]
+        .iter()
+        .take_while(move |run| run.start < range.end)
+        .map(move |run| {
+            run.start.max(range.start) - range.start..run.end.min(range.end) - range.start
+        })
+        .filter(|run| !run.is_empty())
+}
+
+/// Mirrors sorted runs onto the reverse strand of a sequence of length `len`.
+pub(crate) fn reverse_runs(runs: &[Range<usize>], len: usize) -> Vec<Range<usize>> {
+    runs.iter()
+        .rev()
+        .map(|run| len - run.end..len - run.start)
+        .collect()
+}
+
+/// Clears the 2-bit codes covered by `runs` in an encoded buffer.
+pub(crate) fn clear_runs(ebuf: &mut [u64], runs: &[Range<usize>]) {
+    for run in runs {
+        for pos in run.clone() {
+            ebuf[pos / 32] &= !(0b11 << ((pos % 32) * 2));
+        }
+    }
+}
+
+/// Encode a sequence that may contain `N` bases into a buffer of 2-bit encoded nucleotides.
+///
+/// Each `N` (case insensitive) is stored as `A` in the encoded buffer and its position is
+/// recorded as part of a half-open run in `n_runs`, so the original sequence can be restored
+/// with [`decode_with_n`].
+///
+/// # Arguments
+///
+/// * `sequence` - The nucleotide sequence to encode.
+/// * `ebuf` - The buffer to write the encoded nucleotides to.
+/// * `n_runs` - The buffer to write the runs of `N` bases to.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{decode_with_n, encode_with_n};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut ebuf = Vec::new();
+/// let mut n_runs = Vec::new();
+/// encode_with_n(b"ACNNGTN", &mut ebuf, &mut n_runs)?;
+/// assert_eq!(n_runs, vec![2..4, 6..7]);
+///
+/// let mut dbuf = Vec::new();
+/// decode_with_n(&ebuf, 7, &n_runs, &mut dbuf)?;
+/// assert_eq!(dbuf, b"ACNNGTN");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidBase` if the sequence contains a base other than
+/// A, C, G, T or N. `n_runs` is then left unchanged.
+pub fn encode_with_n(
+    sequence: &[u8],
+    ebuf: &mut Vec<u64>,
+    n_runs: &mut Vec<Range<usize>>,
+) -> Result<(), NucleotideError> {
+    let first_run = n_runs.len();
+    find_runs(sequence, is_n, n_runs);
+    if n_runs.len() == first_run {
+        return encode(sequence, ebuf);
+    }
+
+    // Encode the bases between the runs in place, packing each run as A (zero) bases
+    ebuf.clear();
+    let mut n_bases = 0;
+    for i in first_run..=n_runs.len() {
+        let end = n_runs.get(i).map_or(sequence.len(), |run| run.start);
+        if let Err(err) = encode_append(&sequence[n_bases..end], ebuf, n_bases) {
+            n_runs.truncate(first_run);
+            return Err(err.offset_position(n_bases));
+        }
+        if let Some(run) = n_runs.get(i) {
+            ebuf.resize(run.end.div_ceil(32), 0);
+            n_bases = run.end;
+        }
+    }
+    Ok(())
+}
+
+/// Decode a buffer of 2-bit encoded nucleotides, restoring the `N` bases recorded in `n_runs`.
+///
+/// # Arguments
+///
+/// * `ebuf` - The buffer containing the packed nucleotides.
+/// * `n_bases` - The number of nucleotides to unpack.
+/// * `n_runs` - The sorted runs of `N` bases, as produced by [`encode_with_n`].
+/// * `dbuf` - The buffer to write the unpacked nucleotides to.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidRange` if a run is reversed or extends past `n_bases`,
+/// or any error
+/// returned by [`decode`].
+pub fn decode_with_n(
+    ebuf: &[u64],
+    n_bases: usize,
+    n_runs: &[Range<usize>],
+    dbuf: &mut Vec<u8>,
+) -> Result<(), NucleotideError> {
+    check_runs(n_runs, n_bases)?;
+
+    let offset = dbuf.len();
+    decode(ebuf, n_bases, dbuf)?;
+    for run in n_runs {
+        dbuf[offset + run.start..offset + run.end].fill(b'N');
+    }
+    Ok(())
+}
+
+/// Encode a sequence into a buffer of 2-bit encoded nucleotides, recording its soft-mask.
+///
+/// Encoding is case insensitive, so the runs of lowercase bases are recorded as half-open
+/// ranges in `mask_runs` so the original case can be restored with [`decode_with_soft_mask`].
+///
+/// # Arguments
+///
+/// * `sequence` - The nucleotide sequence to encode.
+/// * `ebuf` - The buffer to write the encoded nucleotides to.
+/// * `mask_runs` - The buffer to write the runs of lowercase bases to.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{decode, decode_with_soft_mask, encode_with_soft_mask};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut ebuf = Vec::new();
+/// let mut mask_runs = Vec::new();
+/// encode_with_soft_mask(b"ACgtaCGT", &mut ebuf, &mut mask_runs)?;
+/// assert_eq!(mask_runs, vec![2..5]);
+///
+/// let mut dbuf = Vec::new();
+/// decode(&ebuf, 8, &mut dbuf)?;
+/// assert_eq!(dbuf, b"ACGTACGT");
+///
+/// dbuf.clear();
+/// decode_with_soft_mask(&ebuf, 8, &mask_runs, &mut dbuf)?;
+/// assert_eq!(dbuf, b"ACgtaCGT");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidBase` if the sequence contains a base other than
+/// A, C, G or T (case insensitive).
+pub fn encode_with_soft_mask(
+    sequence: &[u8],
+    ebuf: &mut Vec<u64>,
+    mask_runs: &mut Vec<Range<usize>>,
+) -> Result<(), NucleotideError> {
+    encode(sequence, ebuf)?;
+    find_runs(sequence, is_soft_masked, mask_runs);
+    Ok(())
+}
+
+/// Decode a buffer of 2-bit encoded nucleotides, lowercasing the bases in `mask_runs`.
+///
+/// # Arguments
+///
+/// * `ebuf` - The buffer containing the packed nucleotides.
+/// * `n_bases` - The number of nucleotides to unpack.
+/// * `mask_runs` - The sorted runs of lowercase bases, as produced by [`encode_with_soft_mask`].
+/// * `dbuf` - The buffer to write the unpacked nucleotides to.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidRange` if a run is reversed or extends past `n_bases`,
+/// or any error
+/// returned by [`decode`].
+pub fn decode_with_soft_mask(
+    ebuf: &[u64],
+    n_bases: usize,
+    mask_runs: &[Range<usize>],
+    dbuf: &mut Vec<u8>,
+) -> Result<(), NucleotideError> {
+    check_runs(mask_runs, n_bases)?;
+
+    let offset = dbuf.len();
+    decode(ebuf, n_bases, dbuf)?;
+    for run in mask_runs {
+        dbuf[offset + run.start..offset + run.end].make_ascii_lowercase();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn test_find_runs() {
+        let mut runs = Vec::new();
+        find_runs(b"NNACnGTNN", is_n, &mut runs);
+        assert_eq!(runs, vec![0..2, 4..5, 7..9]);
+
+        runs.clear();
+        find_runs(b"ACGT", is_n, &mut runs);
+        assert!(runs.is_empty());
+    }
+
+    #[test]
+    fn test_runs_contain() {
+        let runs = vec![2..4, 10..11];
+        let hits: Vec<_> = (0..12).filter(|&i| runs_contain(&runs, i)).collect();
+        assert_eq!(hits, vec![2, 3, 10]);
+        assert!(!runs_contain(&[], 0));
+    }
+
+    #[test]
+    fn test_clip_and_reverse_runs() {
+        let runs = vec![0..3, 5..6, 8..12];
+        assert_eq!(
+            clip_runs(&runs, 2..9).collect::<Vec<_>>(),
+            vec![0..1, 3..4, 6..7]
+        );
+        assert_eq!(clip_runs(&runs, 3..5).count(), 0);
+        assert_eq!(reverse_runs(&runs, 12), vec![0..4, 6..7, 9..12]);
+    }
+
+    #[test]
+    fn test_encode_decode_with_n() -> Result<(), NucleotideError> {
+        let seq = b"NNNNACGTNNACGTACGTACGTACGTACGTACGTACGTNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNACGn";
+        let mut ebuf = Vec::new();
+        let mut n_runs = Vec::new();
+        encode_with_n(seq, &mut ebuf, &mut n_runs)?;
+        assert_eq!(
+            ebuf,
+            crate::encode_alloc(&seq.map(|b| if is_n(b) { b'A' } else { b }))?
+        );
+
+        let mut dbuf = Vec::new();
+        decode_with_n(&ebuf, seq.len(), &n_runs, &mut dbuf)?;
+        assert_eq!(dbuf, seq.to_ascii_uppercase());
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_decode_with_soft_mask() -> Result<(), NucleotideError> {
+        let seq = b"acgtACGTACGTACGTACGTACGTACGTACGTacgtacgtacgtacgtacgtacgtACGTACGTa";
+        let mut ebuf = Vec::new();
+        let mut mask_runs = Vec::new();
+        encode_with_soft_mask(seq, &mut ebuf, &mut mask_runs)?;
+        assert_eq!(ebuf, crate::encode_alloc(&seq.to_ascii_uppercase())?);
+        assert_eq!(mask_runs, vec![0..4, 32..56, 64..65]);
+
+        let mut dbuf = Vec::new();
+        decode_with_soft_mask(&ebuf, seq.len(), &mask_runs, &mut dbuf)?;
+        assert_eq!(dbuf, seq);
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_with_n_invalid() {
+        let mut ebuf = Vec::new();
+        let mut n_runs = Vec::new();
+        assert_eq!(
+            encode_with_n(b"ACNRT", &mut ebuf, &mut n_runs),
+            Err(NucleotideError::InvalidBase {
+                base: b'R',
+                position: 3
+            })
+        );
+        assert!(n_runs.is_empty());
+
+        let mut seq = b"NNACGTNNNACGT".repeat(10);
+        seq[75] = b'X';
+        n_runs.push(0..1);
+        assert_eq!(
+            encode_with_n(&seq, &mut ebuf, &mut n_runs),
+            Err(NucleotideError::InvalidBase {
+                base: b'X',
+                position: 75
+            })
+        );
+        assert_eq!(n_runs, vec![0..1]);
+    }
+
+    #[test]
+    fn test_encode_with_n_word_boundaries() -> Result<(), NucleotideError> {
+        let mut ebuf = Vec::new();
+        let mut n_runs = Vec::new();
+        for (start, end) in [(0, 100), (0, 32), (31, 33), (32, 64), (5, 70), (99, 100)] {
+            let mut seq = b"ACGTTGCA".repeat(13)[..100].to_vec();
+            seq[start..end].fill(b'N');
+            n_runs.clear();
+            encode_with_n(&seq, &mut ebuf, &mut n_runs)?;
+            assert_eq!(n_runs, vec![start..end]);
+            let scrubbed: Vec<u8> = seq
+                .iter()
+                .map(|&b| if is_n(b) { b'A' } else { b })
+                .collect();
+            assert_eq!(ebuf, crate::encode_alloc(&scrubbed)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_with_n_invalid_run() {
+        let ebuf = crate::encode_alloc(b"ACGT").unwrap();
+        let mut dbuf = Vec::new();
+        assert!(matches!(
+            decode_with_n(&ebuf, 4, &[0..1, 3..5], &mut dbuf),
+            Err(NucleotideError::InvalidRange { .. })
+        ));
+
+        // Reversed runs are rejected rather than panicking
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = [0..1, 3..1];
+        assert_eq!(
+            decode_with_n(&ebuf, 4, &reversed, &mut dbuf),
+            Err(NucleotideError::InvalidRange {
+                start: 3,
+                end: 1,
+                length: 4
+            })
+        );
+        assert_eq!(
+            decode_with_soft_mask(&ebuf, 4, &reversed, &mut dbuf),
+            Err(NucleotideError::InvalidRange {
+                start: 3,
+                end: 1,
+                length: 4
+            })
+        );
+        assert!(dbuf.is_empty());
+    }
+}
@@ -1,11 +1,37 @@
 pub mod analysis;
+mod bytes;
+pub(crate) mod dispatch;
 pub mod functions;
+pub mod iupac;
+pub mod kmers;
+pub(crate) mod mask;
 pub mod packing;
+mod rna;
+pub mod scheme;
+pub mod sketch;
+pub mod translate;
 pub mod unpacking;
-
-pub use functions::{hdist, hdist_scalar, split_packed};
-pub use packing::{as_2bit, encode_internal};
-pub use unpacking::{from_2bit, from_2bit_alloc, from_2bit_multi};
+mod validate;
+pub mod word;
+
+pub use bytes::{bytes_as_words, decode_bytes, encode_bytes, words_as_bytes};
+pub use functions::{
+    as_2bit_msb, canonical_2bit, canonical_2bit_u128, concat_packed, decode_msb, encode_append,
+    encode_msb, from_2bit_msb, hdist, hdist_scalar, hdist_scalar_u128, lsb_to_msb, msb_to_lsb,
+    revcomp_2bit, revcomp_2bit_u128, revcomp_packed, split_packed,
+};
+pub use kmers::{
+    CanonicalKmerIter, KmerItem, KmerIter, KmerIterator, KmerPolicy, PackedKmerIter, Strand,
+};
+pub use mask::{decode_with_n, decode_with_soft_mask, encode_with_n, encode_with_soft_mask};
+pub use packing::{
+    as_2bit, as_2bit_u128, as_2bit_with_policy, encode_internal, encode_internal_with_policy,
+    EncodeReport, InvalidBasePolicy,
+};
+pub use rna::{as_2bit_rna, decode_rna, encode_rna, from_2bit_rna, Alphabet};
+pub use sketch::{KmerOrder, MinimizerIter, MixHash, PackedValue, SyncmerIter, SyncmerKind};
+pub use unpacking::{from_2bit, from_2bit_alloc, from_2bit_multi, from_2bit_u128};
+pub use validate::{is_valid_dna, is_valid_dna_uppercase, is_valid_iupac, is_valid_rna};
 
 use crate::NucleotideError;
 
@@ -24,6 +50,50 @@ pub fn encode(sequence: &[u8], ebuf: &mut Vec<u64>) -> Result<(), NucleotideErro
     Ok(())
 }
 
+/// Encode a sequence into a buffer of 2-bit encoded nucleotides, resolving invalid
+/// bases according to `policy`.
+///
+/// Under [`InvalidBasePolicy::Error`] this behaves like [`encode`]. Under the other
+/// policies every invalid base is replaced or skipped, and the returned [`EncodeReport`]
+/// records how many bases were written and the input positions of the invalid bases.
+///
+/// # Arguments
+///
+/// * `sequence` - The nucleotide sequence to encode.
+/// * `ebuf` - The buffer to write the encoded nucleotides to.
+/// * `policy` - How to handle bases other than A, C, G, T.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{decode, encode_with_policy, InvalidBasePolicy};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut ebuf = Vec::new();
+/// let report = encode_with_policy(b"ACGTNNACGT", &mut ebuf, InvalidBasePolicy::Skip)?;
+/// assert_eq!(report.n_bases, 8);
+/// assert_eq!(report.positions, vec![4, 5]);
+///
+/// let mut dbuf = Vec::new();
+/// decode(&ebuf, report.n_bases, &mut dbuf)?;
+/// assert_eq!(dbuf, b"ACGTACGT");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidBase` if the sequence contains an invalid base under
+/// [`InvalidBasePolicy::Error`], or if the replacement base of
+/// [`InvalidBasePolicy::ReplaceWith`] is itself invalid.
+pub fn encode_with_policy(
+    sequence: &[u8],
+    ebuf: &mut Vec<u64>,
+    policy: InvalidBasePolicy,
+) -> Result<EncodeReport, NucleotideError> {
+    encode_internal_with_policy(sequence, ebuf, policy)
+}
+
 /// Encode a sequence into a buffer of 2-bit encoded nucleotides.
 ///
 /// This function allocates a new buffer to store the encoded nucleotides.
@@ -1,5 +1,7 @@
 use super::naive;
+use super::policy::{self, EncodeReport, InvalidBasePolicy};
 use crate::error::NucleotideError;
+use crate::utils::validate;
 use std::arch::aarch64::*;
 
 /// Represents the 2-bit encoding for each nucleotide
@@ -82,12 +84,12 @@ pub fn as_2bit(seq: &[u8]) -> Result<u64, NucleotideError> {
         return naive::as_2bit(seq);
     }
 
-    // Pre-validate all bases using SIMD when possible
-    if let Some(&invalid) = seq
-        .iter()
-        .find(|&&b| !matches!(b, b'A' | b'a' | b'C' | b'c' | b'G' | b'g' | b'T' | b't'))
-    {
-        return Err(NucleotideError::InvalidBase(invalid));
+    // Pre-validate all bases
+    if let Some(position) = validate::naive::first_invalid(seq, &validate::DNA) {
+        return Err(NucleotideError::InvalidBase {
+            base: seq[position],
+            position,
+        });
     }
 
     let mut packed = 0u64;
@@ -192,7 +194,14 @@ pub unsafe fn encode_nucleotides_simd(
         let v0 = vld1q_u8(ip);
         let v1 = vld1q_u8(ip.add(16));
         if !valid_block(v0) || !valid_block(v1) {
-            return Err(NucleotideError::InvalidBase(*ip));
+            let offset = input.len() - left;
+            let position = offset
+                + validate::naive::first_invalid(&input[offset..offset + 32], &validate::DNA)
+                    .unwrap_or(0);
+            return Err(NucleotideError::InvalidBase {
+                base: input[position],
+                position,
+            });
         }
         *out = (encode_16_nucleotides(v0) as u64) | ((encode_16_nucleotides(v1) as u64) << 32);
 
@@ -210,7 +219,13 @@ pub unsafe fn encode_nucleotides_simd(
                 b'c' => 1u64,
                 b'g' => 2u64,
                 b't' => 3u64,
-                _ => return Err(NucleotideError::InvalidBase(*ip.add(i))),
+                _ => {
+                    let position = input.len() - left + i;
+                    return Err(NucleotideError::InvalidBase {
+                        base: input[position],
+                        position,
+                    });
+                }
             } << (2 * i);
         }
         *out = tail;
@@ -227,16 +242,22 @@ pub fn encode_internal(sequence: &[u8], ebuf: &mut Vec<u64>) -> Result<(), Nucle
         return Ok(());
     }
 
-    // If the sequence is large enough and SIMD is supported, use SIMD acceleration
-    #[cfg(all(target_arch = "aarch64", not(feature = "nosimd")))]
-    if std::arch::is_aarch64_feature_detected!("neon") {
-        unsafe {
-            // resize the buffer to fit the number of chunks
-            let n_chunks = sequence.len().div_ceil(32);
-            ebuf.resize(n_chunks, 0);
-            encode_nucleotides_simd(sequence, ebuf)?;
-        }
-        return Ok(());
+    // Only dispatched to once NEON support is known
+    unsafe {
+        // resize the buffer to fit the number of chunks
+        let n_chunks = sequence.len().div_ceil(32);
+        ebuf.resize(n_chunks, 0);
+        encode_nucleotides_simd(sequence, ebuf)?;
     }
     Ok(())
 }
+
+#[inline(always)]
+pub fn encode_internal_with_policy(
+    sequence: &[u8],
+    ebuf: &mut Vec<u64>,
+    policy: InvalidBasePolicy,
+) -> Result<EncodeReport, NucleotideError> {
+    ebuf.clear();
+    policy::encode_chunks(sequence, policy, as_2bit, |bits| ebuf.push(bits))
+}
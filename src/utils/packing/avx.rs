@@ -1,4 +1,6 @@
 use super::naive;
+use super::policy::{self, EncodeReport, InvalidBasePolicy};
+use crate::utils::validate;
 use crate::NucleotideError;
 use std::arch::x86_64::*;
 
@@ -83,11 +85,11 @@ pub fn as_2bit(seq: &[u8]) -> Result<u64, NucleotideError> {
         return naive::as_2bit(seq);
     }
 
-    if let Some(&invalid) = seq
-        .iter()
-        .find(|&&b| !matches!(b, b'A' | b'a' | b'C' | b'c' | b'G' | b'g' | b'T' | b't'))
-    {
-        return Err(NucleotideError::InvalidBase(invalid));
+    if let Some(position) = validate::sse::first_invalid(seq, &validate::DNA) {
+        return Err(NucleotideError::InvalidBase {
+            base: seq[position],
+            position,
+        });
     }
 
     let mut packed = 0u64;
@@ -139,13 +141,22 @@ pub fn encode_internal(sequence: &[u8], ebuf: &mut Vec<u64>) -> Result<(), Nucle
         let r_bounds = l_bounds + 32;
         let chunk = &sequence[l_bounds..r_bounds];
 
-        let bits = as_2bit(chunk)?;
+        let bits = as_2bit(chunk).map_err(|err| err.offset_position(l_bounds))?;
         ebuf.push(bits);
         l_bounds = r_bounds;
     }
 
-    let bits = as_2bit(&sequence[l_bounds..])?;
+    let bits = as_2bit(&sequence[l_bounds..]).map_err(|err| err.offset_position(l_bounds))?;
     ebuf.push(bits);
 
     Ok(())
 }
+
+pub fn encode_internal_with_policy(
+    sequence: &[u8],
+    ebuf: &mut Vec<u64>,
+    policy: InvalidBasePolicy,
+) -> Result<EncodeReport, NucleotideError> {
+    ebuf.clear();
+    policy::encode_chunks(sequence, policy, as_2bit, |bits| ebuf.push(bits))
+}
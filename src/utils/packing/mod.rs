@@ -1,13 +1,17 @@
+use crate::utils::dispatch::{level, SimdLevel};
 use crate::NucleotideError;
 
 #[cfg(all(target_arch = "aarch64", not(feature = "nosimd")))]
 mod aarch64;
 #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
 mod avx;
-mod naive;
+pub(crate) mod naive;
+mod policy;
 #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
 mod sse;
 
+pub use policy::{EncodeReport, InvalidBasePolicy};
+
 /// Converts a nucleotide sequence into a 2-bit packed representation.
 ///
 /// Each nucleotide is encoded using 2 bits:
@@ -66,7 +70,7 @@ mod sse;
 /// // Invalid base
 /// assert!(matches!(
 ///     as_2bit(b"ACGN"),
-///     Err(NucleotideError::InvalidBase(b'N'))
+///     Err(NucleotideError::InvalidBase { base: b'N', position: 3 })
 /// ));
 ///
 /// // Sequence too long
@@ -79,66 +83,128 @@ mod sse;
 /// ```
 #[inline(always)]
 pub fn as_2bit(seq: &[u8]) -> Result<u64, NucleotideError> {
-    #[cfg(all(target_arch = "aarch64", not(feature = "nosimd")))]
-    if std::arch::is_aarch64_feature_detected!("neon") {
-        aarch64::as_2bit(seq)
-    } else {
-        naive::as_2bit(seq)
-    }
-
-    #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
-    if is_x86_feature_detected!("avx2") {
-        // Use 256 bit instructions
-        avx::as_2bit(seq)
-    } else if is_x86_feature_detected!("sse2") {
-        // Fall back to 128bit instructions
-        sse::as_2bit(seq)
-    } else {
-        // Cannot make use of SIMD features
-        naive::as_2bit(seq)
-    }
-
-    // Fall back to naive implemention if:
-    // - SIMD is disabled via nosimd feature
-    // - or SIMD feature is not enabled
-    // - or required CPU features aren't availabe
-    #[cfg(any(
-        feature = "nosimd",
-        all(not(target_arch = "aarch64"), not(target_arch = "x86_64"),)
-    ))]
-    naive::as_2bit(seq)
+    match level() {
+        #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+        SimdLevel::Avx2 => avx::as_2bit(seq),
+        #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+        SimdLevel::Sse2 => sse::as_2bit(seq),
+        #[cfg(all(target_arch = "aarch64", not(feature = "nosimd")))]
+        SimdLevel::Neon => aarch64::as_2bit(seq),
+        SimdLevel::Naive => naive::as_2bit(seq),
+    }
+}
+
+/// Converts a nucleotide sequence of up to 64 bases into a 2-bit packed `u128`.
+///
+/// Uses the same least significant bit first layout as `as_2bit`, so the lower
+/// 64 bits hold the first 32 bases exactly as `as_2bit` would pack them.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidBase` if the sequence contains any characters
+/// other than A,C,G,T (case insensitive).
+///
+/// Returns `NucleotideError::SequenceTooLong` if the input sequence is longer
+/// than 64 bases.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{as_2bit, as_2bit_u128};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let seq = b"ACGTACGTACGTACGTACGTACGTACGTACGTTTGCATGCATGCATGCATGCATGCATG";
+/// let packed = as_2bit_u128(seq)?;
+/// assert_eq!(packed as u64, as_2bit(&seq[..32])?);
+/// assert_eq!((packed >> 64) as u64, as_2bit(&seq[32..])?);
+/// # Ok(())
+/// # }
+/// ```
+#[inline(always)]
+pub fn as_2bit_u128(seq: &[u8]) -> Result<u128, NucleotideError> {
+    if seq.len() > 64 {
+        return Err(NucleotideError::SequenceTooLong(seq.len()));
+    }
+    if seq.len() <= 32 {
+        return as_2bit(seq).map(u128::from);
+    }
+    let lower = as_2bit(&seq[..32])?;
+    let upper = as_2bit(&seq[32..]).map_err(|err| err.offset_position(32))?;
+    Ok(((upper as u128) << 64) | lower as u128)
+}
+
+/// Converts a nucleotide sequence into a 2-bit packed representation, resolving
+/// invalid bases according to `policy`.
+///
+/// Behaves like `as_2bit` under [`InvalidBasePolicy::Error`]. Under the other policies
+/// the returned [`EncodeReport`] records where the invalid bases were and how many
+/// bases were packed.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::SequenceTooLong` if the input sequence is longer
+/// than 32 bases.
+///
+/// Returns `NucleotideError::InvalidBase` if the sequence contains an invalid base under
+/// [`InvalidBasePolicy::Error`], or if the replacement base of
+/// [`InvalidBasePolicy::ReplaceWith`] is itself invalid.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{as_2bit, as_2bit_with_policy, InvalidBasePolicy};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let (packed, report) = as_2bit_with_policy(b"ACNT", InvalidBasePolicy::ReplaceWith(b'G'))?;
+/// assert_eq!(packed, as_2bit(b"ACGT")?);
+/// assert_eq!(report.positions, vec![2]);
+///
+/// let (packed, report) = as_2bit_with_policy(b"ACNT", InvalidBasePolicy::Skip)?;
+/// assert_eq!(packed, as_2bit(b"ACT")?);
+/// assert_eq!(report.n_bases, 3);
+/// # Ok(())
+/// # }
+/// ```
+pub fn as_2bit_with_policy(
+    seq: &[u8],
+    policy: InvalidBasePolicy,
+) -> Result<(u64, EncodeReport), NucleotideError> {
+    if seq.len() > 32 {
+        return Err(NucleotideError::SequenceTooLong(seq.len()));
+    }
+    let mut packed = 0;
+    let report = policy::encode_chunks(seq, policy, as_2bit, |bits| packed = bits)?;
+    Ok((packed, report))
 }
 
 #[inline(always)]
 pub fn encode_internal(seq: &[u8], ebuf: &mut Vec<u64>) -> Result<(), NucleotideError> {
-    #[cfg(all(target_arch = "aarch64", not(feature = "nosimd")))]
-    if std::arch::is_aarch64_feature_detected!("neon") {
-        aarch64::encode_internal(seq, ebuf)
-    } else {
-        naive::encode_internal(seq, ebuf)
-    }
-
-    #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
-    if is_x86_feature_detected!("avx2") {
-        // Use 256 bit instructions
-        avx::encode_internal(seq, ebuf)
-    } else if is_x86_feature_detected!("sse2") {
-        // Fall back to 128bit instructions
-        sse::encode_internal(seq, ebuf)
-    } else {
-        // Cannot make use of SIMD features
-        naive::encode_internal(seq, ebuf)
-    }
-
-    // Fall back to naive implemention if:
-    // - SIMD is disabled via nosimd feature
-    // - or SIMD feature is not enabled
-    // - or required CPU features aren't availabe
-    #[cfg(any(
-        feature = "nosimd",
-        all(not(target_arch = "aarch64"), not(target_arch = "x86_64"),)
-    ))]
-    naive::encode_internal(seq, ebuf)
+    match level() {
+        #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+        SimdLevel::Avx2 => avx::encode_internal(seq, ebuf),
+        #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+        SimdLevel::Sse2 => sse::encode_internal(seq, ebuf),
+        #[cfg(all(target_arch = "aarch64", not(feature = "nosimd")))]
+        SimdLevel::Neon => aarch64::encode_internal(seq, ebuf),
+        SimdLevel::Naive => naive::encode_internal(seq, ebuf),
+    }
+}
+
+#[inline(always)]
+pub fn encode_internal_with_policy(
+    seq: &[u8],
+    ebuf: &mut Vec<u64>,
+    policy: InvalidBasePolicy,
+) -> Result<EncodeReport, NucleotideError> {
+    match level() {
+        #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+        SimdLevel::Avx2 => avx::encode_internal_with_policy(seq, ebuf, policy),
+        #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+        SimdLevel::Sse2 => sse::encode_internal_with_policy(seq, ebuf, policy),
+        #[cfg(all(target_arch = "aarch64", not(feature = "nosimd")))]
+        SimdLevel::Neon => aarch64::encode_internal_with_policy(seq, ebuf, policy),
+        SimdLevel::Naive => naive::encode_internal_with_policy(seq, ebuf, policy),
+    }
 }
 
 #[cfg(test)]
@@ -184,7 +250,144 @@ mod testing {
     #[test]
     fn test_as_2bit_invalid_base() {
         let result = as_2bit(b"ACGN");
-        assert!(matches!(result, Err(NucleotideError::InvalidBase(b'N'))));
+        assert!(matches!(
+            result,
+            Err(NucleotideError::InvalidBase {
+                base: b'N',
+                position: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn test_as_2bit_u128_matches_u64() {
+        let seq = b"ACTGGAAAATTTTAAGGACTGGAAAATTTTAAGGCCCGTAGCATGATCGATCGTAGCTAGTCG";
+        for len in 0..=32 {
+            assert_eq!(
+                as_2bit_u128(&seq[..len]).unwrap(),
+                as_2bit(&seq[..len]).unwrap() as u128
+            );
+        }
+        for len in 33..=seq.len() {
+            let packed = as_2bit_u128(&seq[..len]).unwrap();
+            assert_eq!(packed as u64, as_2bit(&seq[..32]).unwrap());
+            assert_eq!((packed >> 64) as u64, as_2bit(&seq[32..len]).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_as_2bit_u128_errors() {
+        let long_seq = vec![b'A'; 65];
+        assert!(matches!(
+            as_2bit_u128(&long_seq),
+            Err(NucleotideError::SequenceTooLong(65))
+        ));
+
+        let mut seq = vec![b'A'; 64];
+        seq[40] = b'N';
+        assert!(matches!(
+            as_2bit_u128(&seq),
+            Err(NucleotideError::InvalidBase {
+                base: b'N',
+                position: 40
+            })
+        ));
+    }
+
+    #[test]
+    fn test_encode_invalid_base_position_backends_agree() {
+        for (len, position) in [(10, 7), (40, 5), (100, 40), (100, 99), (300, 257)] {
+            let mut seq = b"ACGT".repeat(len / 4);
+            seq[position] = b'n';
+            let expected = Err(NucleotideError::InvalidBase {
+                base: b'n',
+                position,
+            });
+
+            let mut ebuf = Vec::new();
+            assert_eq!(naive::encode_internal(&seq, &mut ebuf), expected);
+            assert_eq!(encode_internal(&seq, &mut ebuf), expected);
+
+            #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+            {
+                if is_x86_feature_detected!("avx2") {
+                    assert_eq!(avx::encode_internal(&seq, &mut ebuf), expected);
+                }
+                assert_eq!(sse::encode_internal(&seq, &mut ebuf), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_with_policy_backends_agree() {
+        let mut seq = b"ACGTTGCAN".repeat(15);
+        seq[50] = b'-';
+        for policy in [
+            InvalidBasePolicy::ReplaceWith(b'c'),
+            InvalidBasePolicy::Random { seed: 3 },
+            InvalidBasePolicy::Skip,
+        ] {
+            let mut expected = Vec::new();
+            let expected_report =
+                naive::encode_internal_with_policy(&seq, &mut expected, policy).unwrap();
+
+            let mut ebuf = Vec::new();
+            let report = encode_internal_with_policy(&seq, &mut ebuf, policy).unwrap();
+            assert_eq!(ebuf, expected);
+            assert_eq!(report, expected_report);
+
+            #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+            {
+                if is_x86_feature_detected!("avx2") {
+                    avx::encode_internal_with_policy(&seq, &mut ebuf, policy).unwrap();
+                    assert_eq!(ebuf, expected);
+                }
+                sse::encode_internal_with_policy(&seq, &mut ebuf, policy).unwrap();
+                assert_eq!(ebuf, expected);
+            }
+        }
+        // The first invalid base is found by the kernel itself
+        let expected = Err(NucleotideError::InvalidBase {
+            base: b'N',
+            position: 8,
+        });
+        let mut ebuf = Vec::new();
+        let policy = InvalidBasePolicy::Error;
+        assert_eq!(
+            encode_internal_with_policy(&seq, &mut ebuf, policy),
+            expected
+        );
+        #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+        {
+            if is_x86_feature_detected!("avx2") {
+                assert_eq!(
+                    avx::encode_internal_with_policy(&seq, &mut ebuf, policy),
+                    expected
+                );
+            }
+            assert_eq!(
+                sse::encode_internal_with_policy(&seq, &mut ebuf, policy),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_as_2bit_with_policy_error() {
+        assert!(matches!(
+            as_2bit_with_policy(b"ACGN", InvalidBasePolicy::Error),
+            Err(NucleotideError::InvalidBase {
+                base: b'N',
+                position: 3
+            })
+        ));
+        assert!(matches!(
+            as_2bit_with_policy(&[b'A'; 33], InvalidBasePolicy::Skip),
+            Err(NucleotideError::SequenceTooLong(33))
+        ));
+        let (packed, report) = as_2bit_with_policy(b"ACGT", InvalidBasePolicy::Error).unwrap();
+        assert_eq!(packed, as_2bit(b"ACGT").unwrap());
+        assert_eq!(report.n_invalid(), 0);
     }
 
     #[test]
@@ -1,20 +1,27 @@
+use super::policy::{self, EncodeReport, InvalidBasePolicy};
 use crate::error::NucleotideError;
+use crate::utils::word::PackedWord;
 
 #[inline(always)]
-pub fn as_2bit(seq: &[u8]) -> Result<u64, NucleotideError> {
-    if seq.len() > 32 {
+pub fn as_2bit<W: PackedWord>(seq: &[u8]) -> Result<W, NucleotideError> {
+    if seq.len() > W::MAX_BASES {
         return Err(NucleotideError::SequenceTooLong(seq.len()));
     }
-    let mut packed = 0u64;
+    let mut packed = W::ZERO;
     for (i, &base) in seq.iter().enumerate() {
         let bits = match base {
             b'A' | b'a' => 0b00,
             b'C' | b'c' => 0b01,
             b'G' | b'g' => 0b10,
             b'T' | b't' => 0b11,
-            invalid => return Err(NucleotideError::InvalidBase(invalid)),
+            invalid => {
+                return Err(NucleotideError::InvalidBase {
+                    base: invalid,
+                    position: i,
+                })
+            }
         };
-        packed |= (bits as u64) << (i * 2);
+        packed = packed | (W::from_bits(bits) << (i * 2));
     }
     Ok(packed)
 }
@@ -31,13 +38,22 @@ pub fn encode_internal(sequence: &[u8], ebuf: &mut Vec<u64>) -> Result<(), Nucle
         let r_bounds = l_bounds + 32;
         let chunk = &sequence[l_bounds..r_bounds];
 
-        let bits = as_2bit(chunk)?;
+        let bits = as_2bit(chunk).map_err(|err| err.offset_position(l_bounds))?;
         ebuf.push(bits);
         l_bounds = r_bounds;
     }
 
-    let bits = as_2bit(&sequence[l_bounds..])?;
+    let bits = as_2bit(&sequence[l_bounds..]).map_err(|err| err.offset_position(l_bounds))?;
     ebuf.push(bits);
 
     Ok(())
 }
+
+pub fn encode_internal_with_policy(
+    sequence: &[u8],
+    ebuf: &mut Vec<u64>,
+    policy: InvalidBasePolicy,
+) -> Result<EncodeReport, NucleotideError> {
+    ebuf.clear();
+    policy::encode_chunks(sequence, policy, as_2bit::<u64>, |bits| ebuf.push(bits))
+}
@@ -0,0 +1,218 @@
+use crate::utils::kmers::{BASE_LOOKUP, INVALID_BASE};
+use crate::NucleotideError;
+
+/// Determines how encoding handles bases other than A, C, G, T.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InvalidBasePolicy {
+    /// Fail with `NucleotideError::InvalidBase` on the first invalid base.
+    #[default]
+    Error,
+    /// Replace every invalid base with the given base (A, C, G or T, case insensitive).
+    ///
+    /// An invalid replacement is reported as `NucleotideError::InvalidBase` at position 0.
+    ReplaceWith(u8),
+    /// Replace every invalid base with a pseudo-random base drawn from a seeded generator.
+    ///
+    /// The same seed and input always produce the same output.
+    Random { seed: u64 },
+    /// Drop invalid bases from the output entirely.
+    Skip,
+}
+
+/// Summary of the invalid bases handled while encoding under an [`InvalidBasePolicy`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EncodeReport {
+    /// Number of bases written to the encoded output
+    pub n_bases: usize,
+    /// Positions in the input of the invalid bases that were replaced or skipped
+    pub positions: Vec<usize>,
+}
+
+impl EncodeReport {
+    /// Returns the number of invalid bases that were replaced or skipped.
+    pub fn n_invalid(&self) -> usize {
+        self.positions.len()
+    }
+}
+
+/// SplitMix64 generator used by [`InvalidBasePolicy::Random`].
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    #[inline(always)]
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+}
+
+#[inline(always)]
+fn is_valid(base: u8) -> bool {
+    BASE_LOOKUP[base as usize] != INVALID_BASE
+}
+
+/// Encodes `sequence` in chunks of up to 32 bases with `kernel`, passing each packed
+/// word to `sink` and resolving invalid bases according to `policy`.
+///
+/// Every chunk is handed to the kernel directly, which validates it while packing.
+/// Only when the kernel rejects a chunk are its invalid bases resolved into a stack
+/// buffer, so every word but the last always holds exactly 32 bases, matching the
+/// layout of `encode`.
+pub(crate) fn encode_chunks(
+    sequence: &[u8],
+    policy: InvalidBasePolicy,
+    kernel: fn(&[u8]) -> Result<u64, NucleotideError>,
+    mut sink: impl FnMut(u64),
+) -> Result<EncodeReport, NucleotideError> {
+    if let InvalidBasePolicy::ReplaceWith(base) = policy {
+        if !is_valid(base) {
+            // The replacement is not part of the input, so it has no position of its own
+            return Err(NucleotideError::InvalidBase { base, position: 0 });
+        }
+    }
+    let mut rng = match policy {
+        InvalidBasePolicy::Random { seed } => SplitMix64(seed),
+        _ => SplitMix64(0),
+    };
+
+    let mut report = EncodeReport::default();
+    let mut scratch = [0u8; 32];
+    let mut pos = 0;
+    while pos < sequence.len() {
+        // Fast path: the kernel validates the window as it packs it
+        let end = (pos + 32).min(sequence.len());
+        let window = &sequence[pos..end];
+        match kernel(window) {
+            Ok(bits) => {
+                sink(bits);
+                report.n_bases += window.len();
+                pos = end;
+                continue;
+            }
+            Err(err @ NucleotideError::InvalidBase { .. }) => {
+                if policy == InvalidBasePolicy::Error {
+                    return Err(err.offset_position(pos));
+                }
+            }
+            Err(err) => return Err(err),
+        }
+
+        // Slow path: resolve the invalid bases into the scratch buffer
+        let mut filled = 0;
+        while filled < 32 && pos < sequence.len() {
+            let base = sequence[pos];
+            if is_valid(base) {
+                scratch[filled] = base;
+                filled += 1;
+            } else {
+                report.positions.push(pos);
+                match policy {
+                    InvalidBasePolicy::ReplaceWith(replacement) => {
+                        scratch[filled] = replacement;
+                        filled += 1;
+                    }
+                    InvalidBasePolicy::Random { .. } => {
+                        scratch[filled] = b"ACGT"[(rng.next_u64() >> 62) as usize];
+                        filled += 1;
+                    }
+                    InvalidBasePolicy::Skip | InvalidBasePolicy::Error => {}
+                }
+            }
+            pos += 1;
+        }
+        if filled > 0 {
+            sink(kernel(&scratch[..filled])?);
+            report.n_bases += filled;
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::utils::packing::naive;
+
+    fn encode(seq: &[u8], policy: InvalidBasePolicy) -> (Vec<u64>, EncodeReport) {
+        let mut ebuf = Vec::new();
+        let report = encode_chunks(seq, policy, naive::as_2bit, |w| ebuf.push(w)).unwrap();
+        (ebuf, report)
+    }
+
+    #[test]
+    fn test_replace_with() {
+        let (ebuf, report) = encode(b"ACNGTRA", InvalidBasePolicy::ReplaceWith(b'G'));
+        assert_eq!(ebuf, vec![naive::as_2bit(b"ACGGTGA").unwrap()]);
+        assert_eq!(report.n_bases, 7);
+        assert_eq!(report.positions, vec![2, 5]);
+        assert_eq!(report.n_invalid(), 2);
+    }
+
+    #[test]
+    fn test_replace_with_invalid_replacement() {
+        let result = encode_chunks(
+            b"ACGT",
+            InvalidBasePolicy::ReplaceWith(b'N'),
+            naive::as_2bit,
+            |_| {},
+        );
+        assert_eq!(
+            result,
+            Err(NucleotideError::InvalidBase {
+                base: b'N',
+                position: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_skip_keeps_words_full() {
+        let mut seq = b"ACGT".repeat(20);
+        seq[3] = b'N';
+        seq[40] = b'-';
+        let (ebuf, report) = encode(&seq, InvalidBasePolicy::Skip);
+        let expected: Vec<u8> = seq.iter().copied().filter(|&b| is_valid(b)).collect();
+        assert_eq!(ebuf, crate::encode_alloc(&expected).unwrap());
+        assert_eq!(report.n_bases, 78);
+        assert_eq!(report.positions, vec![3, 40]);
+    }
+
+    #[test]
+    fn test_skip_all_invalid() {
+        let (ebuf, report) = encode(b"NNNN", InvalidBasePolicy::Skip);
+        assert!(ebuf.is_empty());
+        assert_eq!(report.n_bases, 0);
+        assert_eq!(report.positions, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_random_is_seeded() {
+        let seq = b"NNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNACGT";
+        let (a, report) = encode(seq, InvalidBasePolicy::Random { seed: 42 });
+        let (b, _) = encode(seq, InvalidBasePolicy::Random { seed: 42 });
+        let (c, _) = encode(seq, InvalidBasePolicy::Random { seed: 7 });
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(report.n_bases, seq.len());
+        assert_eq!(report.n_invalid(), 40);
+        assert_eq!(a[1] >> 16, naive::as_2bit::<u64>(b"ACGT").unwrap());
+    }
+
+    #[test]
+    fn test_error_policy() {
+        let mut seq = b"ACGT".repeat(20);
+        seq[45] = b'N';
+        let result = encode_chunks(&seq, InvalidBasePolicy::Error, naive::as_2bit, |_| {});
+        assert_eq!(
+            result,
+            Err(NucleotideError::InvalidBase {
+                base: b'N',
+                position: 45
+            })
+        );
+    }
+}
@@ -0,0 +1,252 @@
+use crate::utils::{decode, from_2bit};
+use crate::{as_2bit, NucleotideError};
+
+/// The nucleotide alphabet a packed sequence is read back in.
+///
+/// Both alphabets share the same 2-bit encoding; `T` and `U` are stored as `0b11`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Alphabet {
+    /// Decode `0b11` as `T`
+    #[default]
+    Dna,
+    /// Decode `0b11` as `U`
+    Rna,
+}
+
+impl Alphabet {
+    /// Returns the base decoded from `0b11` in this alphabet.
+    #[inline(always)]
+    pub const fn thymine(self) -> u8 {
+        match self {
+            Alphabet::Dna => b'T',
+            Alphabet::Rna => b'U',
+        }
+    }
+
+    /// Returns the bases decoded from each 2-bit code in this alphabet.
+    #[inline(always)]
+    pub const fn bases(self) -> [u8; 4] {
+        [b'A', b'C', b'G', self.thymine()]
+    }
+}
+
+/// Copies `seq` into `scratch` with every `U`/`u` replaced by `T`/`t`.
+#[inline(always)]
+fn uracil_to_thymine<'a>(seq: &[u8], scratch: &'a mut [u8; 32]) -> &'a [u8] {
+    for (dst, &base) in scratch.iter_mut().zip(seq) {
+        *dst = match base {
+            b'U' => b'T',
+            b'u' => b't',
+            base => base,
+        };
+    }
+    &scratch[..seq.len()]
+}
+
+/// Replaces every `T` in a decoded buffer with `U`.
+#[inline(always)]
+fn thymine_to_uracil(seq: &mut [u8]) {
+    for base in seq.iter_mut() {
+        if *base == b'T' {
+            *base = b'U';
+        }
+    }
+}
+
+/// Converts an RNA sequence into a 2-bit packed representation.
+///
+/// `U` is packed as `0b11`, the same as `T` in `as_2bit`. `T` is also accepted,
+/// so DNA sequences pack identically.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidBase` if the sequence contains any characters
+/// other than A,C,G,U,T (case insensitive).
+///
+/// Returns `NucleotideError::SequenceTooLong` if the input sequence is longer
+/// than 32 bases.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{as_2bit, as_2bit_rna};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// assert_eq!(as_2bit_rna(b"ACGU")?, 0b11100100);
+/// assert_eq!(as_2bit_rna(b"acgu")?, as_2bit(b"ACGT")?);
+/// # Ok(())
+/// # }
+/// ```
+#[inline(always)]
+pub fn as_2bit_rna(seq: &[u8]) -> Result<u64, NucleotideError> {
+    if seq.len() > 32 {
+        return Err(NucleotideError::SequenceTooLong(seq.len()));
+    }
+    let mut scratch = [0u8; 32];
+    as_2bit(uracil_to_thymine(seq, &mut scratch))
+}
+
+/// Converts a 2-bit packed representation back into an RNA sequence.
+///
+/// This is `from_2bit` with `0b11` decoded as `U`.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidLength` if `expected_size` is greater than 32.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{as_2bit_rna, from_2bit_rna};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut unpacked = Vec::new();
+/// from_2bit_rna(as_2bit_rna(b"GAUUACA")?, 7, &mut unpacked)?;
+/// assert_eq!(unpacked, b"GAUUACA");
+/// # Ok(())
+/// # }
+/// ```
+#[inline(always)]
+pub fn from_2bit_rna(
+    packed: u64,
+    expected_size: usize,
+    sequence: &mut Vec<u8>,
+) -> Result<(), NucleotideError> {
+    let offset = sequence.len();
+    from_2bit(packed, expected_size, sequence)?;
+    thymine_to_uracil(&mut sequence[offset..]);
+    Ok(())
+}
+
+/// Encode an RNA sequence into a buffer of 2-bit encoded nucleotides.
+///
+/// The output is identical to `encode` of the same sequence with `U` written as `T`.
+///
+/// # Arguments
+///
+/// * `sequence` - The nucleotide sequence to encode.
+/// * `ebuf` - The buffer to write the encoded nucleotides to.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{encode_alloc, encode_rna};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut ebuf = Vec::new();
+/// encode_rna(b"ACGUACGUACGUACGUACGUACGUACGUACGUUUGCA", &mut ebuf)?;
+/// assert_eq!(ebuf, encode_alloc(b"ACGTACGTACGTACGTACGTACGTACGTACGTTTGCA")?);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidBase` if the sequence contains any characters
+/// other than A,C,G,U,T (case insensitive).
+pub fn encode_rna(sequence: &[u8], ebuf: &mut Vec<u64>) -> Result<(), NucleotideError> {
+    ebuf.clear();
+    ebuf.reserve(sequence.len().div_ceil(32));
+    for (i, chunk) in sequence.chunks(32).enumerate() {
+        ebuf.push(as_2bit_rna(chunk).map_err(|err| err.offset_position(i * 32))?);
+    }
+    Ok(())
+}
+
+/// Decode a buffer of 2-bit encoded nucleotides as RNA.
+///
+/// # Arguments
+///
+/// * `ebuf` - The buffer containing the packed nucleotides.
+/// * `n_bases` - The number of nucleotides to unpack.
+/// * `dbuf` - The buffer to write the unpacked nucleotides to.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{decode_rna, encode_rna};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut ebuf = Vec::new();
+/// encode_rna(b"ACGUUGCA", &mut ebuf)?;
+///
+/// let mut dbuf = Vec::new();
+/// decode_rna(&ebuf, 8, &mut dbuf)?;
+/// assert_eq!(dbuf, b"ACGUUGCA");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns any error returned by [`decode`].
+pub fn decode_rna(ebuf: &[u64], n_bases: usize, dbuf: &mut Vec<u8>) -> Result<(), NucleotideError> {
+    let offset = dbuf.len();
+    decode(ebuf, n_bases, dbuf)?;
+    thymine_to_uracil(&mut dbuf[offset..]);
+    Ok(())
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::{encode, encode_alloc};
+    use nucgen::Sequence;
+
+    #[test]
+    fn test_rna_roundtrip() {
+        let mut rng = rand::thread_rng();
+        let mut seq = Sequence::new();
+
+        for len in [1, 31, 32, 33, 100, 1000] {
+            seq.fill_buffer(&mut rng, len);
+            let rna: Vec<u8> = seq
+                .bytes()
+                .iter()
+                .map(|&b| if b == b'T' { b'U' } else { b })
+                .collect();
+
+            let mut ebuf = Vec::new();
+            encode_rna(&rna, &mut ebuf).unwrap();
+            assert_eq!(ebuf, encode_alloc(seq.bytes()).unwrap());
+
+            let mut dbuf = vec![b'>'];
+            decode_rna(&ebuf, len, &mut dbuf).unwrap();
+            assert_eq!(dbuf[0], b'>');
+            assert_eq!(&dbuf[1..], rna);
+        }
+    }
+
+    #[test]
+    fn test_rna_invalid_base() {
+        assert_eq!(
+            as_2bit_rna(b"ACGX"),
+            Err(NucleotideError::InvalidBase {
+                base: b'X',
+                position: 3
+            })
+        );
+        assert_eq!(
+            as_2bit_rna(&[b'U'; 33]),
+            Err(NucleotideError::SequenceTooLong(33))
+        );
+        let mut ebuf = Vec::new();
+        assert_eq!(
+            encode_rna(b"ACGUACGUACGUACGUACGUACGUACGUACGUAN", &mut ebuf),
+            Err(NucleotideError::InvalidBase {
+                base: b'N',
+                position: 33
+            })
+        );
+
+        // The DNA encoder is unchanged
+        assert!(encode(b"ACGU", &mut ebuf).is_err());
+    }
+
+    #[test]
+    fn test_rna_empty() {
+        let mut ebuf = vec![1];
+        encode_rna(b"", &mut ebuf).unwrap();
+        assert!(ebuf.is_empty());
+    }
+}
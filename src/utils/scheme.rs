@@ -0,0 +1,450 @@
+//! Alternative 2-bit base orders.
+//!
+//! The crate level functions use the [`Acgt`] order (A=00, C=01, G=10, T=11). Other tools
+//! order the bases differently, so this module provides an [`EncodingScheme`] trait whose
+//! methods pack, unpack and compare sequences in any order, and [`transcode`] to convert
+//! packed buffers between orders without decoding them.
+//!
+//! ```rust
+//! use bitnuc::scheme::{transcode, Acgt, EncodingScheme, Tcag};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! // UCSC .2bit order: T=00, C=01, A=10, G=11
+//! assert_eq!(Tcag::as_2bit(b"TCAG")?, 0b11100100);
+//!
+//! let mut ebuf = Vec::new();
+//! Acgt::encode(b"ACGTTGCA", &mut ebuf)?;
+//! transcode::<Acgt, Tcag>(&mut ebuf, 8)?;
+//!
+//! let mut dbuf = Vec::new();
+//! Tcag::decode(&ebuf, 8, &mut dbuf)?;
+//! assert_eq!(dbuf, b"ACGTTGCA");
+//! # Ok(())
+//! # }
+//! ```
+
+use std::marker::PhantomData;
+
+use crate::utils::word::PackedWord;
+use crate::{decode, encode, from_2bit, hdist, hdist_scalar, NucleotideError};
+
+/// The lower bit of every 2-bit group
+const LOWER_BITS: u64 = 0x5555_5555_5555_5555;
+
+/// A mapping between the four nucleotides and their 2-bit codes.
+///
+/// Every method has a default implementation, so a scheme only needs to list its
+/// bases in code order and the XOR mask that maps a code to its complement.
+///
+/// Both constants are checked at compile time: `BASES` must be a permutation of
+/// `b"ACGT"` and `COMPLEMENT` must map A to T and C to G.
+///
+/// ```compile_fail
+/// use bitnuc::scheme::EncodingScheme;
+///
+/// struct Lowercase;
+///
+/// impl EncodingScheme for Lowercase {
+///     const BASES: [u8; 4] = *b"acgt";
+///     const COMPLEMENT: u8 = 0b11;
+/// }
+///
+/// let _ = Lowercase::as_2bit(b"ACGT");
+/// ```
+pub trait EncodingScheme: Sized {
+    /// The bases in code order, i.e. `BASES[code]` is the base packed as `code`
+    const BASES: [u8; 4];
+    /// XOR-ing a code with this mask yields the code of its complement
+    const COMPLEMENT: u8;
+
+    /// Converts a nucleotide sequence of up to 32 bases into a 2-bit packed word.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NucleotideError::InvalidBase` if the sequence contains any characters
+    /// other than A,C,G,T (case insensitive).
+    ///
+    /// Returns `NucleotideError::SequenceTooLong` if the input sequence is longer
+    /// than 32 bases.
+    fn as_2bit(seq: &[u8]) -> Result<u64, NucleotideError> {
+        let packed = crate::as_2bit(seq)?;
+        Ok(transcode_word::<Acgt, Self>(packed, seq.len()))
+    }
+
+    /// Converts a 2-bit packed word back into a nucleotide sequence.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NucleotideError::InvalidLength` if `expected_size` is greater than 32.
+    fn from_2bit(
+        packed: u64,
+        expected_size: usize,
+        sequence: &mut Vec<u8>,
+    ) -> Result<(), NucleotideError> {
+        if expected_size > 32 {
+            return Err(NucleotideError::InvalidLength(expected_size));
+        }
+        from_2bit(
+            transcode_word::<Self, Acgt>(packed, expected_size),
+            expected_size,
+            sequence,
+        )
+    }
+
+    /// Encode a sequence into a buffer of 2-bit encoded nucleotides.
+    ///
+    /// # Errors
+    ///
+    /// If the sequence cannot be encoded, an error is returned.
+    fn encode(sequence: &[u8], ebuf: &mut Vec<u64>) -> Result<(), NucleotideError> {
+        encode(sequence, ebuf)?;
+        transcode::<Acgt, Self>(ebuf, sequence.len())
+    }
+
+    /// Decode a buffer of 2-bit encoded nucleotides.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NucleotideError::InvalidLength` if `ebuf` holds fewer than `n_bases` bases.
+    fn decode(ebuf: &[u64], n_bases: usize, dbuf: &mut Vec<u8>) -> Result<(), NucleotideError> {
+        if is_identity::<Self, Acgt>() {
+            return decode(ebuf, n_bases, dbuf);
+        }
+        if ebuf.len() < n_bases.div_ceil(32) {
+            return Err(NucleotideError::InvalidLength(n_bases));
+        }
+        dbuf.reserve(n_bases);
+        for (i, &packed) in ebuf.iter().take(n_bases.div_ceil(32)).enumerate() {
+            Self::from_2bit(packed, (n_bases - i * 32).min(32), dbuf)?;
+        }
+        Ok(())
+    }
+
+    /// Calculates the hamming distance between two 2-bit packed words.
+    ///
+    /// A mismatch is a mismatch in every base order, so this is the same as
+    /// [`hdist_scalar`](crate::hdist_scalar) as long as both words use this scheme.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NucleotideError::InvalidLength` if `len` is greater than 32.
+    fn hdist_scalar(u: u64, v: u64, len: usize) -> Result<u32, NucleotideError> {
+        hdist_scalar(u, v, len)
+    }
+
+    /// Calculates the hamming distance between two buffers of 2-bit encoded nucleotides.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NucleotideError::InvalidLength` if either buffer holds fewer than
+    /// `n_bases` bases.
+    fn hdist(ebuf1: &[u64], ebuf2: &[u64], n_bases: usize) -> Result<u32, NucleotideError> {
+        hdist(ebuf1, ebuf2, n_bases)
+    }
+
+    /// Calculates the reverse complement of a 2-bit packed word of up to 32 bases.
+    fn revcomp_2bit(packed: u64, len: usize) -> u64 {
+        debug_assert!(len <= 32, "revcomp_2bit only supports up to 32 bases");
+        if len == 0 {
+            return 0;
+        }
+        let complement = Self::COMPLEMENT as u64 * LOWER_BITS;
+        (packed.reverse_2bit_groups() ^ complement) >> (64 - 2 * len)
+    }
+}
+
+/// The default order used throughout the crate: A=00, C=01, G=10, T=11.
+///
+/// The complement of a code is its bitwise NOT.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Acgt;
+
+impl EncodingScheme for Acgt {
+    const BASES: [u8; 4] = *b"ACGT";
+    const COMPLEMENT: u8 = 0b11;
+
+    #[inline(always)]
+    fn as_2bit(seq: &[u8]) -> Result<u64, NucleotideError> {
+        crate::as_2bit(seq)
+    }
+
+    #[inline(always)]
+    fn from_2bit(
+        packed: u64,
+        expected_size: usize,
+        sequence: &mut Vec<u8>,
+    ) -> Result<(), NucleotideError> {
+        from_2bit(packed, expected_size, sequence)
+    }
+
+    #[inline(always)]
+    fn encode(sequence: &[u8], ebuf: &mut Vec<u64>) -> Result<(), NucleotideError> {
+        encode(sequence, ebuf)
+    }
+}
+
+/// The order A=00, C=01, T=10, G=11, in which the complement of a code is the code XOR 2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Actg;
+
+impl EncodingScheme for Actg {
+    const BASES: [u8; 4] = *b"ACTG";
+    const COMPLEMENT: u8 = 0b10;
+}
+
+/// The UCSC `.2bit` order: T=00, C=01, A=10, G=11.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Tcag;
+
+impl EncodingScheme for Tcag {
+    const BASES: [u8; 4] = *b"TCAG";
+    const COMPLEMENT: u8 = 0b10;
+}
+
+/// Returns true if both schemes assign the same code to every base.
+#[inline(always)]
+fn is_identity<From: EncodingScheme, To: EncodingScheme>() -> bool {
+    From::BASES == To::BASES
+}
+
+/// Returns the code of each of A, C, G and T under a scheme with the given constants.
+///
+/// Panics if `bases` is not a permutation of `b"ACGT"` or if `complement` does not map
+/// A to T and C to G. Only evaluated in const context, so a bad scheme fails to compile.
+const fn scheme_codes(bases: [u8; 4], complement: u8) -> [u64; 4] {
+    const UNSET: u64 = 4;
+    let mut codes = [UNSET; 4];
+    let mut code = 0;
+    while code < 4 {
+        let index = match bases[code] {
+            b'A' => 0,
+            b'C' => 1,
+            b'G' => 2,
+            b'T' => 3,
+            _ => panic!("EncodingScheme::BASES must be a permutation of b\"ACGT\""),
+        };
+        assert!(
+            codes[index] == UNSET,
+            "EncodingScheme::BASES must be a permutation of b\"ACGT\""
+        );
+        codes[index] = code as u64;
+        code += 1;
+    }
+    let complement = complement as u64;
+    assert!(
+        complement < 4 && codes[0] ^ complement == codes[3] && codes[1] ^ complement == codes[2],
+        "EncodingScheme::COMPLEMENT must map A to T and C to G"
+    );
+    codes
+}
+
+/// The validated codes of a scheme, checked when the scheme is first used.
+struct SchemeCodes<S>(PhantomData<S>);
+
+impl<S: EncodingScheme> SchemeCodes<S> {
+    /// The code of each of A, C, G and T
+    const CODES: [u64; 4] = scheme_codes(S::BASES, S::COMPLEMENT);
+}
+
+/// Returns the code in `To` of every code in `From`.
+#[inline(always)]
+fn permutation<From: EncodingScheme, To: EncodingScheme>() -> [u64; 4] {
+    let from = SchemeCodes::<From>::CODES;
+    let to = SchemeCodes::<To>::CODES;
+    let mut perm = [0; 4];
+    for (from_code, to_code) in from.into_iter().zip(to) {
+        perm[from_code as usize] = to_code;
+    }
+    perm
+}
+
+/// Remaps every 2-bit group of `packed` through `perm`.
+#[inline(always)]
+fn remap(packed: u64, perm: &[u64; 4]) -> u64 {
+    let lo = packed & LOWER_BITS;
+    let hi = (packed >> 1) & LOWER_BITS;
+
+    // One bit per base at the bottom of its 2-bit group, set where the code matches
+    let is_0 = !(lo | hi) & LOWER_BITS;
+    let is_1 = lo & !hi;
+    let is_2 = hi & !lo;
+    let is_3 = lo & hi;
+
+    // Each product writes a code below 4 into the groups of its mask, so they never overlap
+    is_0.wrapping_mul(perm[0])
+        | is_1.wrapping_mul(perm[1])
+        | is_2.wrapping_mul(perm[2])
+        | is_3.wrapping_mul(perm[3])
+}
+
+/// Converts a 2-bit packed word of `n_bases` bases from the `From` scheme to the `To` scheme.
+///
+/// Bits past `n_bases` are cleared.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::scheme::{transcode_word, Acgt, EncodingScheme, Tcag};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let packed = Acgt::as_2bit(b"GATTACA")?;
+/// assert_eq!(transcode_word::<Acgt, Tcag>(packed, 7), Tcag::as_2bit(b"GATTACA")?);
+/// # Ok(())
+/// # }
+/// ```
+#[inline]
+pub fn transcode_word<From: EncodingScheme, To: EncodingScheme>(
+    packed: u64,
+    n_bases: usize,
+) -> u64 {
+    let perm = permutation::<From, To>();
+    let mask = u64::mask(n_bases);
+    if is_identity::<From, To>() {
+        return packed & mask;
+    }
+    remap(packed, &perm) & mask
+}
+
+/// Converts a buffer of 2-bit encoded nucleotides from the `From` scheme to the `To` scheme
+/// in place.
+///
+/// The bits past `n_bases` in the last word are cleared, so the output matches
+/// `To::encode` of the same sequence.
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidLength` if `ebuf` holds fewer than `n_bases` bases.
+pub fn transcode<From: EncodingScheme, To: EncodingScheme>(
+    ebuf: &mut [u64],
+    n_bases: usize,
+) -> Result<(), NucleotideError> {
+    let perm = permutation::<From, To>();
+    let n_chunks = n_bases.div_ceil(32);
+    if ebuf.len() < n_chunks {
+        return Err(NucleotideError::InvalidLength(n_bases));
+    }
+    if n_chunks == 0 {
+        return Ok(());
+    }
+
+    let (full, last) = ebuf[..n_chunks].split_at_mut(n_chunks - 1);
+    if !is_identity::<From, To>() {
+        for word in full.iter_mut() {
+            *word = remap(*word, &perm);
+        }
+    }
+    last[0] = transcode_word::<From, To>(last[0], n_bases - (n_chunks - 1) * 32);
+    Ok(())
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use nucgen::Sequence;
+
+    fn check_scheme<S: EncodingScheme>() {
+        let mut rng = rand::thread_rng();
+        let mut seq = Sequence::new();
+
+        for len in [1, 7, 31, 32, 33, 64, 100, 1000] {
+            seq.fill_buffer(&mut rng, len);
+            let bytes = seq.bytes();
+
+            // Reference packing straight from the scheme's base order
+            let reference: Vec<u64> = bytes
+                .chunks(32)
+                .map(|chunk| {
+                    chunk.iter().enumerate().fold(0u64, |acc, (i, &b)| {
+                        let code = S::BASES.iter().position(|&c| c == b).unwrap();
+                        acc | (code as u64) << (i * 2)
+                    })
+                })
+                .collect();
+
+            let mut ebuf = Vec::new();
+            S::encode(bytes, &mut ebuf).unwrap();
+            assert_eq!(ebuf, reference);
+            assert_eq!(S::as_2bit(&bytes[..len.min(32)]).unwrap(), reference[0]);
+
+            let mut dbuf = Vec::new();
+            S::decode(&ebuf, len, &mut dbuf).unwrap();
+            assert_eq!(dbuf, bytes);
+
+            // Round trip through every other scheme
+            let mut acgt = ebuf.clone();
+            transcode::<S, Acgt>(&mut acgt, len).unwrap();
+            assert_eq!(acgt, crate::encode_alloc(bytes).unwrap());
+            let mut tcag = acgt.clone();
+            transcode::<Acgt, Tcag>(&mut tcag, len).unwrap();
+            transcode::<Tcag, S>(&mut tcag, len).unwrap();
+            assert_eq!(tcag, ebuf);
+        }
+    }
+
+    #[test]
+    fn test_schemes() {
+        check_scheme::<Acgt>();
+        check_scheme::<Actg>();
+        check_scheme::<Tcag>();
+    }
+
+    #[test]
+    fn test_scheme_codes() {
+        assert_eq!(Actg::as_2bit(b"ACTG").unwrap(), 0b11100100);
+        assert_eq!(Tcag::as_2bit(b"TCAG").unwrap(), 0b11100100);
+        assert_eq!(Tcag::as_2bit(b"tcag").unwrap(), 0b11100100);
+        assert!(matches!(
+            Tcag::as_2bit(b"TCAN"),
+            Err(NucleotideError::InvalidBase {
+                base: b'N',
+                position: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn test_scheme_complement() {
+        fn check<S: EncodingScheme>() {
+            let seq = b"AACGTTGCAGGT";
+            let rc = S::revcomp_2bit(S::as_2bit(seq).unwrap(), seq.len());
+            assert_eq!(rc, S::as_2bit(b"ACCTGCAACGTT").unwrap());
+
+            for (base, complement) in [(b'A', b'T'), (b'C', b'G')] {
+                let code = S::as_2bit(&[base]).unwrap();
+                assert_eq!(
+                    code ^ S::COMPLEMENT as u64,
+                    S::as_2bit(&[complement]).unwrap()
+                );
+            }
+        }
+        check::<Acgt>();
+        check::<Actg>();
+        check::<Tcag>();
+    }
+
+    #[test]
+    fn test_scheme_hdist() {
+        fn check<S: EncodingScheme>() {
+            let a = b"ACGTACGTACGTACGTACGTACGTACGTACGTACGTAAAA";
+            let b = b"ACGTACGTACCTACGTACGTACGTACGTACGTACGTAAAT";
+            let (mut ua, mut ub) = (Vec::new(), Vec::new());
+            S::encode(a, &mut ua).unwrap();
+            S::encode(b, &mut ub).unwrap();
+            assert_eq!(S::hdist(&ua, &ub, a.len()).unwrap(), 2);
+            assert_eq!(S::hdist_scalar(ua[1], ub[1], 8).unwrap(), 1);
+        }
+        check::<Acgt>();
+        check::<Actg>();
+        check::<Tcag>();
+    }
+
+    #[test]
+    fn test_transcode_short_buffer() {
+        let mut ebuf = vec![0u64];
+        assert_eq!(
+            transcode::<Acgt, Tcag>(&mut ebuf, 33),
+            Err(NucleotideError::InvalidLength(33))
+        );
+        transcode::<Acgt, Tcag>(&mut [], 0).unwrap();
+    }
+}
@@ -0,0 +1,293 @@
+use std::collections::VecDeque;
+use std::iter::FusedIterator;
+
+use super::KmerOrder;
+use crate::{KmerItem, NucleotideError, Strand};
+
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    pos: usize,
+    key: u64,
+    kmer: u64,
+    strand: Strand,
+}
+
+/// Iterates over the `(w, k)` minimizers of a sequence.
+///
+/// Wraps any of the k-mer iterators and selects the k-mer with the smallest key
+/// in every window of `w` consecutive k-mers, breaking ties by the leftmost position.
+/// Each selected k-mer is yielded once as a `(position, kmer, strand)` tuple, even if
+/// it is the minimizer of several overlapping windows.
+///
+/// Candidates are kept in a monotone deque, so each k-mer is pushed and popped at most
+/// once and selection runs in amortized O(1) per base.
+///
+/// Gaps in the k-mer positions (e.g. k-mers skipped with `KmerPolicy::Skip`) restart the
+/// window, and runs with fewer than `w` k-mers yield no minimizers.
+///
+/// # Examples
+///
+/// Forward minimizers of raw bytes ordered by packed value:
+///
+/// ```rust
+/// use bitnuc::{as_2bit, KmerIter, KmerPolicy, MinimizerIter, PackedValue, Strand};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let kmers = KmerIter::new(b"TTTACAGGT", 3, KmerPolicy::Error)?;
+/// let minimizers: Vec<_> = MinimizerIter::new(kmers, 3, PackedValue)?.collect();
+/// assert_eq!(
+///     minimizers,
+///     vec![
+///         (1, as_2bit(b"TTA")?, Strand::Forward),
+///         (3, as_2bit(b"ACA")?, Strand::Forward),
+///         (4, as_2bit(b"CAG")?, Strand::Forward),
+///     ]
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Canonical minimizers of an encoded buffer ordered by a hash:
+///
+/// ```rust
+/// use bitnuc::{as_2bit, encode_alloc, MinimizerIter, MixHash, PackedKmerIter, Strand};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let seq = b"ACGTTGCAGGCTAGCTAGCTAGGCATCGATCGACTACGACTAGC";
+/// let ebuf = encode_alloc(seq)?;
+///
+/// let kmers = PackedKmerIter::new(&ebuf, seq.len(), 15)?.canonical();
+/// let minimizers: Vec<_> = MinimizerIter::new(kmers, 10, MixHash)?.collect();
+/// assert_eq!(
+///     minimizers,
+///     vec![
+///         // GGCTAGCTAGCTAGG is smaller on the reverse strand
+///         (8, as_2bit(b"CCTAGCTAGCTAGCC")?, Strand::Reverse),
+///         (14, as_2bit(b"CTAGCTAGGCATCGA")?, Strand::Forward),
+///         (22, as_2bit(b"GCATCGATCGACTAC")?, Strand::Forward),
+///     ]
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MinimizerIter<I, O> {
+    inner: I,
+    w: usize,
+    order: O,
+    window: VecDeque<Candidate>,
+    /// Position the next k-mer must have to extend the current window
+    next_pos: Option<usize>,
+    /// Number of consecutive k-mers seen since the last gap
+    run: usize,
+    /// Position of the last yielded minimizer
+    last: Option<usize>,
+}
+
+impl<I, O> MinimizerIter<I, O>
+where
+    I: Iterator,
+    I::Item: KmerItem,
+    O: KmerOrder,
+{
+    /// Creates a new minimizer iterator.
+    ///
+    /// # Arguments
+    ///
+    /// * `kmers` - An iterator over k-mers, e.g. [`KmerIter`](crate::KmerIter),
+    ///   [`PackedKmerIter`](crate::PackedKmerIter) or their canonical forms
+    /// * `w` - The number of consecutive k-mers in each window
+    /// * `order` - The ordering used to compare k-mers
+    ///
+    /// # Errors
+    ///
+    /// Returns `NucleotideError::InvalidLength` if `w` is zero.
+    pub fn new(kmers: I, w: usize, order: O) -> Result<Self, NucleotideError> {
+        if w == 0 {
+            return Err(NucleotideError::InvalidLength(w));
+        }
+        Ok(Self {
+            inner: kmers,
+            w,
+            order,
+            window: VecDeque::with_capacity(w),
+            next_pos: None,
+            run: 0,
+            last: None,
+        })
+    }
+
+    /// Returns the number of k-mers in each window.
+    pub fn w(&self) -> usize {
+        self.w
+    }
+}
+
+impl<I, O> Iterator for MinimizerIter<I, O>
+where
+    I: Iterator,
+    I::Item: KmerItem,
+    O: KmerOrder,
+{
+    type Item = (usize, u64, Strand);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (pos, kmer, strand) = self.inner.next()?.into_parts();
+
+            // Restart the window after a gap
+            if self.next_pos != Some(pos) {
+                self.window.clear();
+                self.run = 0;
+            }
+            self.next_pos = Some(pos + 1);
+            self.run += 1;
+
+            // Drop candidates that can no longer be the minimum
+            let key = self.order.key(kmer);
+            while self.window.back().is_some_and(|c| c.key > key) {
+                self.window.pop_back();
+            }
+            self.window.push_back(Candidate {
+                pos,
+                key,
+                kmer,
+                strand,
+            });
+
+            // Drop candidates that have left the window
+            while self.window.front().is_some_and(|c| c.pos + self.w <= pos) {
+                self.window.pop_front();
+            }
+
+            if self.run >= self.w {
+                let min = self.window[0];
+                if self.last != Some(min.pos) {
+                    self.last = Some(min.pos);
+                    return Some((min.pos, min.kmer, min.strand));
+                }
+            }
+        }
+    }
+}
+
+impl<I, O> FusedIterator for MinimizerIter<I, O>
+where
+    I: FusedIterator,
+    I::Item: KmerItem,
+    O: KmerOrder,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{encode_alloc, KmerIter, KmerPolicy, MixHash, PackedKmerIter, PackedValue};
+    use nucgen::Sequence;
+
+    /// Selects minimizers by scanning every window of consecutive k-mers
+    fn brute_force<T: KmerItem>(
+        kmers: impl Iterator<Item = T>,
+        w: usize,
+        order: &impl KmerOrder,
+    ) -> Vec<(usize, u64, Strand)> {
+        let kmers: Vec<_> = kmers.map(KmerItem::into_parts).collect();
+
+        // Split into runs of consecutive positions
+        let mut runs: Vec<Vec<(usize, u64, Strand)>> = Vec::new();
+        for kmer in kmers {
+            match runs.last_mut() {
+                Some(run) if run.last().unwrap().0 + 1 == kmer.0 => run.push(kmer),
+                _ => runs.push(vec![kmer]),
+            }
+        }
+
+        let mut minimizers: Vec<(usize, u64, Strand)> = Vec::new();
+        for run in runs {
+            for window in run.windows(w) {
+                let min = window
+                    .iter()
+                    .min_by_key(|(pos, kmer, _)| (order.key(*kmer), *pos))
+                    .unwrap();
+                if minimizers.last().map(|m| m.0) != Some(min.0) {
+                    minimizers.push(*min);
+                }
+            }
+        }
+        minimizers
+    }
+
+    #[test]
+    fn test_minimizers_match_brute_force() {
+        let mut rng = rand::thread_rng();
+        let mut seq = Sequence::new();
+        seq.fill_buffer(&mut rng, 500);
+        let ebuf = encode_alloc(seq.bytes()).unwrap();
+        let len = seq.bytes().len();
+
+        for (k, w) in [
+            (1, 1),
+            (5, 1),
+            (7, 4),
+            (15, 10),
+            (21, 11),
+            (31, 5),
+            (32, 20),
+        ] {
+            let kmers = KmerIter::new(seq.bytes(), k, KmerPolicy::Error).unwrap();
+            let expected = brute_force(kmers.clone(), w, &MixHash);
+            let observed: Vec<_> = MinimizerIter::new(kmers, w, MixHash).unwrap().collect();
+            assert_eq!(observed, expected, "Failed for k = {}, w = {}", k, w);
+
+            let kmers = PackedKmerIter::new(&ebuf, len, k).unwrap();
+            let observed: Vec<_> = MinimizerIter::new(kmers, w, MixHash).unwrap().collect();
+            assert_eq!(observed, expected, "Failed for packed k = {}, w = {}", k, w);
+
+            let kmers = PackedKmerIter::new(&ebuf, len, k).unwrap().canonical();
+            let expected = brute_force(kmers.clone(), w, &PackedValue);
+            let observed: Vec<_> = MinimizerIter::new(kmers, w, PackedValue).unwrap().collect();
+            assert_eq!(
+                observed, expected,
+                "Failed for canonical k = {}, w = {}",
+                k, w
+            );
+        }
+    }
+
+    #[test]
+    fn test_minimizers_restart_after_skip() {
+        let seq = b"ACGGTTACGATNCATGCATGACGATCGANNGGGACCATTAGCA";
+        let kmers = KmerIter::new(seq, 4, KmerPolicy::Skip).unwrap();
+        let expected = brute_force(kmers.clone(), 3, &PackedValue);
+        let observed: Vec<_> = MinimizerIter::new(kmers, 3, PackedValue).unwrap().collect();
+        assert_eq!(observed, expected);
+        assert!(observed.iter().all(|(pos, _, _)| !(8..=11).contains(pos)));
+    }
+
+    #[test]
+    fn test_minimizers_custom_order() {
+        // Reversing the order selects maximizers, AAT has the largest packed value
+        let order = |kmer: u64| u64::MAX - kmer;
+        let kmers = KmerIter::new(b"AAAATAAAA", 3, KmerPolicy::Error).unwrap();
+        let observed: Vec<_> = MinimizerIter::new(kmers, 7, order).unwrap().collect();
+        assert_eq!(
+            observed,
+            vec![(2, crate::as_2bit(b"AAT").unwrap(), Strand::Forward)]
+        );
+    }
+
+    #[test]
+    fn test_minimizers_short_sequence() {
+        let kmers = KmerIter::new(b"ACGTA", 3, KmerPolicy::Error).unwrap();
+        assert_eq!(MinimizerIter::new(kmers, 4, MixHash).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_minimizers_invalid_window() {
+        let kmers = KmerIter::new(b"ACGTA", 3, KmerPolicy::Error).unwrap();
+        assert!(matches!(
+            MinimizerIter::new(kmers, 0, MixHash),
+            Err(NucleotideError::InvalidLength(0))
+        ));
+    }
+}
@@ -0,0 +1,51 @@
+mod minimizer;
+mod syncmer;
+
+pub use minimizer::MinimizerIter;
+pub use syncmer::{SyncmerIter, SyncmerKind};
+
+/// Defines the order in which k-mers are compared when selecting minimizers and syncmers.
+///
+/// The k-mer with the smallest key is selected. Any `Fn(u64) -> u64` closure can be used
+/// as an ordering.
+pub trait KmerOrder {
+    /// Returns the sort key of a packed k-mer.
+    fn key(&self, kmer: u64) -> u64;
+}
+
+impl<F: Fn(u64) -> u64> KmerOrder for F {
+    #[inline(always)]
+    fn key(&self, kmer: u64) -> u64 {
+        self(kmer)
+    }
+}
+
+/// Orders k-mers by their packed value.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PackedValue;
+
+impl KmerOrder for PackedValue {
+    #[inline(always)]
+    fn key(&self, kmer: u64) -> u64 {
+        kmer
+    }
+}
+
+/// Orders k-mers by a 64-bit mixing hash of their packed value.
+///
+/// Uses the MurmurHash3 finalizer, which is a bijection so distinct k-mers never collide.
+/// This avoids the bias towards low-complexity k-mers (e.g. poly-A) of [`PackedValue`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MixHash;
+
+impl KmerOrder for MixHash {
+    #[inline(always)]
+    fn key(&self, kmer: u64) -> u64 {
+        let mut h = kmer;
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xff51afd7ed558ccd);
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xc4ceb9fe1a85ec53);
+        h ^ (h >> 33)
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::VecDeque;
+use std::iter::FusedIterator;
+
+use super::KmerOrder;
+use crate::{KmerIterator, NucleotideError};
+
+/// The rule used to select syncmers from the position of their minimal s-mer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncmerKind {
+    /// Select k-mers whose minimal s-mer starts at the given offset.
+    Open {
+        /// The offset `t` of the minimal s-mer within the k-mer (0 to k - s)
+        offset: usize,
+    },
+    /// Select k-mers whose minimal s-mer is either the first or the last s-mer.
+    Closed,
+}
+
+/// Iterates over the syncmers of a sequence.
+///
+/// A k-mer is a syncmer if its minimal s-mer (the s-mer with the smallest key,
+/// breaking ties by the leftmost position) appears at a specific offset within it.
+/// Unlike minimizers, the selection depends only on the k-mer itself.
+///
+/// Wraps one of the forward k-mer iterators and yields the selected `(position, kmer)`
+/// pairs. The s-mers are tracked in a monotone deque so that each base adds a single
+/// s-mer, and gaps in the k-mer positions (e.g. k-mers skipped with `KmerPolicy::Skip`)
+/// restart the deque.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{as_2bit, KmerIter, KmerPolicy, MixHash, SyncmerIter, SyncmerKind};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let seq = b"ACGTTGCAGGCTAGCTAGCTAGGCATCGATCGACTACGACTAGC";
+///
+/// let kmers = KmerIter::new(seq, 15, KmerPolicy::Skip)?;
+/// let closed: Vec<_> = SyncmerIter::new(kmers, 5, SyncmerKind::Closed, MixHash)?.collect();
+/// let expected = [7, 10, 12, 22, 28]
+///     .into_iter()
+///     .map(|pos| Ok((pos, as_2bit(&seq[pos..pos + 15])?)))
+///     .collect::<Result<Vec<_>, bitnuc::NucleotideError>>()?;
+/// assert_eq!(closed, expected);
+///
+/// let kmers = KmerIter::new(seq, 15, KmerPolicy::Skip)?;
+/// let open = SyncmerIter::new(kmers, 5, SyncmerKind::Open { offset: 5 }, MixHash)?;
+/// assert_eq!(open.map(|(pos, _)| pos).collect::<Vec<_>>(), [2, 17, 26]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SyncmerIter<I, O> {
+    inner: I,
+    k: usize,
+    s: usize,
+    kind: SyncmerKind,
+    order: O,
+    smer_mask: u64,
+    /// Candidate minimal s-mers as `(position, key)` pairs
+    smers: VecDeque<(usize, u64)>,
+    /// Position the next k-mer must have to extend the current s-mers
+    next_pos: Option<usize>,
+}
+
+impl<I, O> SyncmerIter<I, O>
+where
+    I: KmerIterator,
+    O: KmerOrder,
+{
+    /// Creates a new syncmer iterator.
+    ///
+    /// # Arguments
+    ///
+    /// * `kmers` - An iterator over k-mers, either [`KmerIter`](crate::KmerIter)
+    ///   or [`PackedKmerIter`](crate::PackedKmerIter)
+    /// * `s` - The s-mer size (1 to k)
+    /// * `kind` - Whether to select open or closed syncmers
+    /// * `order` - The ordering used to compare s-mers
+    ///
+    /// # Errors
+    ///
+    /// Returns `NucleotideError::InvalidLength` if `s` is zero or greater than k.
+    ///
+    /// Returns `NucleotideError::IndexOutOfBounds` if the open syncmer offset is
+    /// greater than k - s.
+    pub fn new(kmers: I, s: usize, kind: SyncmerKind, order: O) -> Result<Self, NucleotideError> {
+        let k = kmers.k();
+        if s == 0 || s > k {
+            return Err(NucleotideError::InvalidLength(s));
+        }
+        if let SyncmerKind::Open { offset } = kind {
+            if offset > k - s {
+                return Err(NucleotideError::IndexOutOfBounds {
+                    index: offset,
+                    length: k - s + 1,
+                });
+            }
+        }
+        let smer_mask = if s == 32 {
+            u64::MAX
+        } else {
+            (1u64 << (s * 2)) - 1
+        };
+        Ok(Self {
+            inner: kmers,
+            k,
+            s,
+            kind,
+            order,
+            smer_mask,
+            smers: VecDeque::with_capacity(k - s + 1),
+            next_pos: None,
+        })
+    }
+
+    #[inline(always)]
+    fn push_smer(&mut self, kmer: u64, pos: usize, offset: usize) {
+        let key = self.order.key((kmer >> (offset * 2)) & self.smer_mask);
+        while self.smers.back().is_some_and(|&(_, k)| k > key) {
+            self.smers.pop_back();
+        }
+        self.smers.push_back((pos + offset, key));
+    }
+}
+
+impl<I, O> Iterator for SyncmerIter<I, O>
+where
+    I: KmerIterator,
+    O: KmerOrder,
+{
+    type Item = (usize, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let last_offset = self.k - self.s;
+        loop {
+            let (pos, kmer) = self.inner.next()?;
+
+            if self.next_pos == Some(pos) {
+                // Only the last s-mer of the k-mer is new
+                self.push_smer(kmer, pos, last_offset);
+            } else {
+                // Restart with every s-mer of the k-mer after a gap
+                self.smers.clear();
+                for offset in 0..=last_offset {
+                    self.push_smer(kmer, pos, offset);
+                }
+            }
+            self.next_pos = Some(pos + 1);
+
+            // Drop s-mers that start before the k-mer
+            while self.smers.front().is_some_and(|&(p, _)| p < pos) {
+                self.smers.pop_front();
+            }
+
+            let min_offset = self.smers[0].0 - pos;
+            let selected = match self.kind {
+                SyncmerKind::Open { offset } => min_offset == offset,
+                SyncmerKind::Closed => min_offset == 0 || min_offset == last_offset,
+            };
+            if selected {
+                return Some((pos, kmer));
+            }
+        }
+    }
+}
+
+impl<I, O> FusedIterator for SyncmerIter<I, O>
+where
+    I: KmerIterator + FusedIterator,
+    O: KmerOrder,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{encode_alloc, KmerIter, KmerPolicy, MixHash, PackedKmerIter, PackedValue};
+    use nucgen::Sequence;
+
+    /// Selects syncmers by scanning every s-mer of every k-mer
+    fn brute_force(
+        kmers: impl KmerIterator,
+        s: usize,
+        kind: SyncmerKind,
+        order: &impl KmerOrder,
+    ) -> Vec<(usize, u64)> {
+        let k = kmers.k();
+        let mask = (1u64 << (s * 2)) - 1;
+        kmers
+            .filter(|&(_, kmer)| {
+                let min_offset = (0..=k - s)
+                    .min_by_key(|&offset| (order.key((kmer >> (offset * 2)) & mask), offset))
+                    .unwrap();
+                match kind {
+                    SyncmerKind::Open { offset } => min_offset == offset,
+                    SyncmerKind::Closed => min_offset == 0 || min_offset == k - s,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_syncmers_match_brute_force() {
+        let mut rng = rand::thread_rng();
+        let mut seq = Sequence::new();
+        seq.fill_buffer(&mut rng, 500);
+        let ebuf = encode_alloc(seq.bytes()).unwrap();
+        let len = seq.bytes().len();
+
+        for (k, s) in [(1, 1), (5, 2), (15, 5), (21, 11), (31, 8), (32, 31)] {
+            for kind in [
+                SyncmerKind::Closed,
+                SyncmerKind::Open { offset: 0 },
+                SyncmerKind::Open {
+                    offset: (k - s) / 2,
+                },
+            ] {
+                let kmers = KmerIter::new(seq.bytes(), k, KmerPolicy::Error).unwrap();
+                let expected = brute_force(kmers.clone(), s, kind, &MixHash);
+                let observed: Vec<_> = SyncmerIter::new(kmers, s, kind, MixHash).unwrap().collect();
+                assert_eq!(observed, expected, "Failed for k = {}, s = {}", k, s);
+
+                let kmers = PackedKmerIter::new(&ebuf, len, k).unwrap();
+                let observed: Vec<_> = SyncmerIter::new(kmers, s, kind, MixHash).unwrap().collect();
+                assert_eq!(observed, expected, "Failed for packed k = {}, s = {}", k, s);
+            }
+        }
+    }
+
+    #[test]
+    fn test_syncmers_restart_after_skip() {
+        let seq = b"ACGGTTACGATNCATGCATGACGATCGANNGGGACCATTAGCA";
+        let kmers = KmerIter::new(seq, 6, KmerPolicy::Skip).unwrap();
+        let expected = brute_force(kmers.clone(), 3, SyncmerKind::Closed, &PackedValue);
+        let observed: Vec<_> = SyncmerIter::new(kmers, 3, SyncmerKind::Closed, PackedValue)
+            .unwrap()
+            .collect();
+        assert_eq!(observed, expected);
+    }
+
+    #[test]
+    fn test_syncmers_invalid_parameters() {
+        let kmers = KmerIter::new(b"ACGTACGT", 5, KmerPolicy::Error).unwrap();
+        assert!(matches!(
+            SyncmerIter::new(kmers.clone(), 0, SyncmerKind::Closed, MixHash),
+            Err(NucleotideError::InvalidLength(0))
+        ));
+        assert!(matches!(
+            SyncmerIter::new(kmers.clone(), 6, SyncmerKind::Closed, MixHash),
+            Err(NucleotideError::InvalidLength(6))
+        ));
+        assert!(matches!(
+            SyncmerIter::new(kmers, 3, SyncmerKind::Open { offset: 3 }, MixHash),
+            Err(NucleotideError::IndexOutOfBounds {
+                index: 3,
+                length: 3
+            })
+        ));
+    }
+}
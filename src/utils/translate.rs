@@ -0,0 +1,491 @@
+//! Translation of packed nucleotides into amino acids.
+//!
+//! Three packed bases form a 6-bit codon index, so each codon is translated with a
+//! single table lookup and the sequence is never decoded to ASCII. The tables follow
+//! the [NCBI genetic codes](https://www.ncbi.nlm.nih.gov/Taxonomy/Utils/wprintgc.cgi),
+//! with stop codons translated as `*`.
+//!
+//! ```rust
+//! use bitnuc::translate::{translate, Frame, GeneticCode};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let seq = b"ATGGCCTGAAAA";
+//! let ebuf = bitnuc::encode_alloc(seq)?;
+//!
+//! let mut protein = Vec::new();
+//! translate(&ebuf, seq.len(), Frame::Forward0, &GeneticCode::STANDARD, false, &mut protein)?;
+//! assert_eq!(protein, b"MA*K");
+//!
+//! protein.clear();
+//! translate(&ebuf, seq.len(), Frame::Forward0, &GeneticCode::VERTEBRATE_MITOCHONDRIAL, false, &mut protein)?;
+//! assert_eq!(protein, b"MAWK");
+//! # Ok(())
+//! # }
+//! ```
+
+use std::ops::Range;
+
+use crate::{NucleotideError, Strand};
+
+/// The amino acid emitted for stop codons
+pub const STOP: u8 = b'*';
+
+/// The amino acid emitted for codons overlapping an `N`
+pub const UNKNOWN: u8 = b'X';
+
+/// A reading frame of a nucleotide sequence.
+///
+/// Reverse frames read the reverse complement, so `Reverse0` starts at the last base
+/// of the sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Frame {
+    /// Forward strand, starting at base 0
+    Forward0,
+    /// Forward strand, starting at base 1
+    Forward1,
+    /// Forward strand, starting at base 2
+    Forward2,
+    /// Reverse strand, starting at the last base
+    Reverse0,
+    /// Reverse strand, starting at the second to last base
+    Reverse1,
+    /// Reverse strand, starting at the third to last base
+    Reverse2,
+}
+
+impl Frame {
+    /// All six frames, forward frames first
+    pub const ALL: [Frame; 6] = [
+        Frame::Forward0,
+        Frame::Forward1,
+        Frame::Forward2,
+        Frame::Reverse0,
+        Frame::Reverse1,
+        Frame::Reverse2,
+    ];
+
+    /// Returns the strand the frame is read from.
+    pub fn strand(self) -> Strand {
+        match self {
+            Frame::Forward0 | Frame::Forward1 | Frame::Forward2 => Strand::Forward,
+            Frame::Reverse0 | Frame::Reverse1 | Frame::Reverse2 => Strand::Reverse,
+        }
+    }
+
+    /// Returns the number of bases skipped at the start of the strand.
+    pub fn offset(self) -> usize {
+        match self {
+            Frame::Forward0 | Frame::Reverse0 => 0,
+            Frame::Forward1 | Frame::Reverse1 => 1,
+            Frame::Forward2 | Frame::Reverse2 => 2,
+        }
+    }
+}
+
+/// Maps a 2-bit code (A=00, C=01, G=10, T=11) to its position in NCBI's TCAG order.
+const TCAG_INDEX: [usize; 4] = [2, 1, 3, 0];
+
+/// Returns the packed reverse complement of a packed codon.
+#[inline(always)]
+const fn revcomp_codon(codon: usize) -> usize {
+    (((codon & 0b11) << 4) | (codon & 0b1100) | (codon >> 4)) ^ 0x3F
+}
+
+/// Reorders an NCBI amino acid string (TCAG codon order) by packed codon.
+const fn forward_table(ncbi: &[u8; 64]) -> [u8; 64] {
+    let mut table = [0u8; 64];
+    let mut codon = 0;
+    while codon < 64 {
+        let first = TCAG_INDEX[codon & 0b11];
+        let second = TCAG_INDEX[(codon >> 2) & 0b11];
+        let third = TCAG_INDEX[codon >> 4];
+        table[codon] = ncbi[first * 16 + second * 4 + third];
+        codon += 1;
+    }
+    table
+}
+
+/// Indexes a forward table by the packed reverse complement of each codon.
+const fn reverse_table(forward: &[u8; 64]) -> [u8; 64] {
+    let mut table = [0u8; 64];
+    let mut codon = 0;
+    while codon < 64 {
+        table[codon] = forward[revcomp_codon(codon)];
+        codon += 1;
+    }
+    table
+}
+
+/// A genetic code mapping every codon to an amino acid.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GeneticCode {
+    id: u8,
+    name: &'static str,
+    /// Amino acids indexed by packed codon
+    forward: [u8; 64],
+    /// Amino acids indexed by the packed reverse complement of the codon
+    reverse: [u8; 64],
+}
+
+macro_rules! genetic_codes {
+    ($($(#[$doc:meta])* $konst:ident = ($id:literal, $name:literal, $aas:literal);)*) => {
+        impl GeneticCode {
+            $(
+                $(#[$doc])*
+                pub const $konst: GeneticCode = GeneticCode::from_ncbi($id, $name, $aas);
+            )*
+
+            /// All supported genetic codes, ordered by NCBI identifier
+            pub const ALL: &'static [GeneticCode] = &[$(GeneticCode::$konst),*];
+        }
+    };
+}
+
+genetic_codes! {
+    /// NCBI table 1
+    STANDARD = (1, "Standard", b"FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG");
+    /// NCBI table 2
+    VERTEBRATE_MITOCHONDRIAL = (2, "Vertebrate Mitochondrial", b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSS**VVVVAAAADDEEGGGG");
+    /// NCBI table 3
+    YEAST_MITOCHONDRIAL = (3, "Yeast Mitochondrial", b"FFLLSSSSYY**CCWWTTTTPPPPHHQQRRRRIIMMTTTTNNKKSSRRVVVVAAAADDEEGGGG");
+    /// NCBI table 4
+    MOLD_MITOCHONDRIAL = (4, "Mold, Protozoan, and Coelenterate Mitochondrial and Mycoplasma/Spiroplasma", b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG");
+    /// NCBI table 5
+    INVERTEBRATE_MITOCHONDRIAL = (5, "Invertebrate Mitochondrial", b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSSSSVVVVAAAADDEEGGGG");
+    /// NCBI table 6
+    CILIATE_NUCLEAR = (6, "Ciliate, Dasycladacean and Hexamita Nuclear", b"FFLLSSSSYYQQCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG");
+    /// NCBI table 9
+    ECHINODERM_MITOCHONDRIAL = (9, "Echinoderm and Flatworm Mitochondrial", b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNNKSSSSVVVVAAAADDEEGGGG");
+    /// NCBI table 10
+    EUPLOTID_NUCLEAR = (10, "Euplotid Nuclear", b"FFLLSSSSYY**CCCWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG");
+    /// NCBI table 11, which shares its amino acids with the standard code
+    BACTERIAL = (11, "Bacterial, Archaeal and Plant Plastid", b"FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG");
+    /// NCBI table 12
+    ALTERNATIVE_YEAST_NUCLEAR = (12, "Alternative Yeast Nuclear", b"FFLLSSSSYY**CC*WLLLSPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG");
+    /// NCBI table 13
+    ASCIDIAN_MITOCHONDRIAL = (13, "Ascidian Mitochondrial", b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSSGGVVVVAAAADDEEGGGG");
+    /// NCBI table 14
+    ALTERNATIVE_FLATWORM_MITOCHONDRIAL = (14, "Alternative Flatworm Mitochondrial", b"FFLLSSSSYYY*CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNNKSSSSVVVVAAAADDEEGGGG");
+    /// NCBI table 16
+    CHLOROPHYCEAN_MITOCHONDRIAL = (16, "Chlorophycean Mitochondrial", b"FFLLSSSSYY*LCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG");
+    /// NCBI table 21
+    TREMATODE_MITOCHONDRIAL = (21, "Trematode Mitochondrial", b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNNKSSSSVVVVAAAADDEEGGGG");
+    /// NCBI table 22
+    SCENEDESMUS_MITOCHONDRIAL = (22, "Scenedesmus obliquus Mitochondrial", b"FFLLSS*SYY*LCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG");
+    /// NCBI table 23
+    THRAUSTOCHYTRIUM_MITOCHONDRIAL = (23, "Thraustochytrium Mitochondrial", b"FF*LSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG");
+    /// NCBI table 24
+    RHABDOPLEURIDAE_MITOCHONDRIAL = (24, "Rhabdopleuridae Mitochondrial", b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSSKVVVVAAAADDEEGGGG");
+    /// NCBI table 25
+    SR1_GRACILIBACTERIA = (25, "Candidate Division SR1 and Gracilibacteria", b"FFLLSSSSYY**CCGWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG");
+    /// NCBI table 26
+    PACHYSOLEN_NUCLEAR = (26, "Pachysolen tannophilus Nuclear", b"FFLLSSSSYY**CC*WLLLAPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG");
+    /// NCBI table 29
+    MESODINIUM_NUCLEAR = (29, "Mesodinium Nuclear", b"FFLLSSSSYYYYCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG");
+    /// NCBI table 30
+    PERITRICH_NUCLEAR = (30, "Peritrich Nuclear", b"FFLLSSSSYYEECC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG");
+    /// NCBI table 31
+    BLASTOCRITHIDIA_NUCLEAR = (31, "Blastocrithidia Nuclear", b"FFLLSSSSYYEECCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG");
+    /// NCBI table 33
+    CEPHALODISCIDAE_MITOCHONDRIAL = (33, "Cephalodiscidae Mitochondrial", b"FFLLSSSSYYY*CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSSKVVVVAAAADDEEGGGG");
+}
+
+impl GeneticCode {
+    /// Builds a genetic code from an NCBI amino acid string, listing the amino acid of
+    /// every codon in TCAG order (TTT, TTC, TTA, TTG, TCT, ...).
+    pub const fn from_ncbi(id: u8, name: &'static str, amino_acids: &[u8; 64]) -> Self {
+        let forward = forward_table(amino_acids);
+        Self {
+            id,
+            name,
+            reverse: reverse_table(&forward),
+            forward,
+        }
+    }
+
+    /// Returns the genetic code with the given NCBI identifier, if it is supported.
+    ///
+    /// Tables 27, 28 and 32 assign context-dependent meanings to their stop codons
+    /// and are not supported.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bitnuc::translate::GeneticCode;
+    ///
+    /// assert_eq!(GeneticCode::from_ncbi_id(2), Some(&GeneticCode::VERTEBRATE_MITOCHONDRIAL));
+    /// assert_eq!(GeneticCode::from_ncbi_id(7), None);
+    /// ```
+    pub fn from_ncbi_id(id: u8) -> Option<&'static GeneticCode> {
+        Self::ALL.iter().find(|code| code.id == id)
+    }
+
+    /// Returns the NCBI identifier of the code.
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    /// Returns the NCBI name of the code.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Translates a codon packed as by `as_2bit`.
+    ///
+    /// Only the lowest 6 bits of `codon` are read.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bitnuc::{as_2bit, translate::GeneticCode};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// assert_eq!(GeneticCode::STANDARD.translate_codon(as_2bit(b"ATG")?), b'M');
+    /// assert_eq!(GeneticCode::STANDARD.translate_codon(as_2bit(b"TAA")?), b'*');
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn translate_codon(&self, codon: u64) -> u8 {
+        self.forward[(codon & 0x3F) as usize]
+    }
+
+    /// Returns true if the packed codon is a stop codon.
+    #[inline(always)]
+    pub fn is_stop(&self, codon: u64) -> bool {
+        self.translate_codon(codon) == STOP
+    }
+}
+
+/// Reads the 3 bases starting at `pos` as a 6-bit codon index.
+#[inline(always)]
+fn codon_at(ebuf: &[u64], pos: usize) -> usize {
+    let word = pos / 32;
+    let shift = (pos % 32) * 2;
+    let mut bits = ebuf[word] >> shift;
+    if shift > 58 {
+        bits |= ebuf[word + 1] << (64 - shift);
+    }
+    (bits & 0x3F) as usize
+}
+
+/// Returns true if any of the `n_runs` overlaps the codon starting at `pos`.
+#[inline(always)]
+fn overlaps_n(n_runs: &[Range<usize>], pos: usize) -> bool {
+    let idx = n_runs.partition_point(|run| run.end <= pos);
+    n_runs.get(idx).is_some_and(|run| run.start < pos + 3)
+}
+
+/// Translates one frame of a buffer of 2-bit encoded nucleotides into amino acids.
+///
+/// The amino acids are appended to `abuf`, one byte per complete codon, with stop
+/// codons written as `*`. If `to_stop` is set, translation ends before the first
+/// stop codon instead.
+///
+/// # Arguments
+///
+/// * `ebuf` - The buffer containing the packed nucleotides.
+/// * `n_bases` - The number of nucleotides in the buffer.
+/// * `frame` - The reading frame to translate.
+/// * `code` - The genetic code to translate with.
+/// * `to_stop` - Whether to stop at the first stop codon.
+/// * `abuf` - The buffer to write the amino acids to.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::translate::{translate, Frame, GeneticCode};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let seq = b"CCATGGCCCTGTAAGG";
+/// let ebuf = bitnuc::encode_alloc(seq)?;
+///
+/// let mut protein = Vec::new();
+/// translate(&ebuf, seq.len(), Frame::Forward2, &GeneticCode::STANDARD, true, &mut protein)?;
+/// assert_eq!(protein, b"MAL");
+///
+/// // The reverse complement is CCTTACAGGGCCATGG
+/// protein.clear();
+/// translate(&ebuf, seq.len(), Frame::Reverse0, &GeneticCode::STANDARD, false, &mut protein)?;
+/// assert_eq!(protein, b"PYRAM");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidLength` if `ebuf` holds fewer than `n_bases` bases.
+pub fn translate(
+    ebuf: &[u64],
+    n_bases: usize,
+    frame: Frame,
+    code: &GeneticCode,
+    to_stop: bool,
+    abuf: &mut Vec<u8>,
+) -> Result<(), NucleotideError> {
+    translate_with_n(ebuf, n_bases, &[], frame, code, to_stop, abuf)
+}
+
+/// Translates one frame, writing `X` for every codon overlapping one of `n_runs`.
+pub(crate) fn translate_with_n(
+    ebuf: &[u64],
+    n_bases: usize,
+    n_runs: &[Range<usize>],
+    frame: Frame,
+    code: &GeneticCode,
+    to_stop: bool,
+    abuf: &mut Vec<u8>,
+) -> Result<(), NucleotideError> {
+    if ebuf.len() < n_bases.div_ceil(32) {
+        return Err(NucleotideError::InvalidLength(n_bases));
+    }
+    let n_codons = n_bases.saturating_sub(frame.offset()) / 3;
+    abuf.reserve(n_codons);
+
+    for i in 0..n_codons {
+        // The forward position of the codon's lowest base
+        let (pos, table) = match frame.strand() {
+            Strand::Forward => (frame.offset() + i * 3, &code.forward),
+            Strand::Reverse => (n_bases - frame.offset() - (i + 1) * 3, &code.reverse),
+        };
+        let amino_acid = if overlaps_n(n_runs, pos) {
+            UNKNOWN
+        } else {
+            table[codon_at(ebuf, pos)]
+        };
+        if to_stop && amino_acid == STOP {
+            break;
+        }
+        abuf.push(amino_acid);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::{as_2bit, encode_alloc};
+    use nucgen::Sequence;
+
+    /// Translates an ASCII sequence with the NCBI string directly.
+    fn reference(seq: &[u8], ncbi: &[u8; 64]) -> Vec<u8> {
+        let tcag = |b: u8| b"TCAG".iter().position(|&x| x == b).unwrap();
+        seq.chunks_exact(3)
+            .map(|c| ncbi[tcag(c[0]) * 16 + tcag(c[1]) * 4 + tcag(c[2])])
+            .collect()
+    }
+
+    fn revcomp(seq: &[u8]) -> Vec<u8> {
+        seq.iter()
+            .rev()
+            .map(|&b| match b {
+                b'A' => b'T',
+                b'C' => b'G',
+                b'G' => b'C',
+                _ => b'A',
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_all_frames_match_reference() {
+        let ncbi = b"FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG";
+        let mut rng = rand::thread_rng();
+        let mut seq = Sequence::new();
+
+        for len in [0, 2, 3, 31, 32, 33, 64, 65, 97, 1000] {
+            seq.fill_buffer(&mut rng, len);
+            let bytes = seq.bytes();
+            let ebuf = if len == 0 {
+                Vec::new()
+            } else {
+                encode_alloc(bytes).unwrap()
+            };
+            let rc = revcomp(bytes);
+
+            for frame in Frame::ALL {
+                let strand = match frame.strand() {
+                    Strand::Forward => bytes,
+                    Strand::Reverse => &rc[..],
+                };
+                let expected = reference(&strand[frame.offset().min(len)..], ncbi);
+
+                let mut protein = Vec::new();
+                translate(
+                    &ebuf,
+                    len,
+                    frame,
+                    &GeneticCode::STANDARD,
+                    false,
+                    &mut protein,
+                )
+                .unwrap();
+                assert_eq!(protein, expected, "{frame:?} of length {len}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_tables() {
+        for code in GeneticCode::ALL {
+            assert_eq!(GeneticCode::from_ncbi_id(code.id()), Some(code));
+        }
+        let std = &GeneticCode::STANDARD;
+        let mito = &GeneticCode::VERTEBRATE_MITOCHONDRIAL;
+        let codon = |c: &[u8]| as_2bit(c).unwrap();
+
+        assert_eq!(std.translate_codon(codon(b"TGA")), STOP);
+        assert_eq!(mito.translate_codon(codon(b"TGA")), b'W');
+        assert_eq!(std.translate_codon(codon(b"ATA")), b'I');
+        assert_eq!(mito.translate_codon(codon(b"ATA")), b'M');
+        assert!(mito.is_stop(codon(b"AGA")));
+        assert_eq!(
+            GeneticCode::YEAST_MITOCHONDRIAL.translate_codon(codon(b"CTG")),
+            b'T'
+        );
+        assert_eq!(GeneticCode::BACTERIAL.forward, std.forward);
+        assert_eq!(GeneticCode::from_ncbi_id(0), None);
+    }
+
+    #[test]
+    fn test_to_stop_and_n() {
+        let ebuf = encode_alloc(b"ATGAAATAGCCC").unwrap();
+        let mut protein = Vec::new();
+        translate(
+            &ebuf,
+            12,
+            Frame::Forward0,
+            &GeneticCode::STANDARD,
+            true,
+            &mut protein,
+        )
+        .unwrap();
+        assert_eq!(protein, b"MK");
+
+        protein.clear();
+        let n_runs = [4..5, 11..12];
+        translate_with_n(
+            &ebuf,
+            12,
+            &n_runs,
+            Frame::Forward0,
+            &GeneticCode::STANDARD,
+            false,
+            &mut protein,
+        )
+        .unwrap();
+        assert_eq!(protein, b"MX*X");
+
+        assert_eq!(
+            translate(
+                &ebuf,
+                40,
+                Frame::Forward0,
+                &GeneticCode::STANDARD,
+                false,
+                &mut protein
+            ),
+            Err(NucleotideError::InvalidLength(40))
+        );
+    }
+}
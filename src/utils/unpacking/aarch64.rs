@@ -125,8 +125,13 @@ pub unsafe fn decode_nucleotides_simd(
 }
 
 pub fn fast_decode(enc: &[u64], len: usize, out: &mut Vec<u8>) -> Result<(), NucleotideError> {
-    out.resize(len, 0);
-    unsafe { decode_nucleotides_simd(enc, len, out) }
+    if enc.len() < len.div_ceil(32) {
+        return Err(NucleotideError::InvalidLength(len));
+    }
+    // Decode after any bases already in the buffer
+    let start = out.len();
+    out.resize(start + len, 0);
+    unsafe { decode_nucleotides_simd(enc, len, &mut out[start..]) }
 }
 
 #[cfg(test)]
@@ -119,6 +119,9 @@ pub unsafe fn from_2bit_multi_simd(
     n_bases: usize,
     sequence: &mut Vec<u8>,
 ) -> Result<(), NucleotideError> {
+    if ebuf.len() < n_bases.div_ceil(32) {
+        return Err(NucleotideError::InvalidLength(n_bases));
+    }
     sequence.reserve(n_bases);
 
     // Set up SIMD lookup table once for all chunks
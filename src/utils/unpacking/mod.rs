@@ -2,8 +2,9 @@
 mod aarch64;
 #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
 mod avx;
-mod naive;
+pub(crate) mod naive;
 
+use crate::utils::dispatch::{level, SimdLevel};
 use crate::NucleotideError;
 
 /// Converts an arbitrary sized 2-bit packed representation back into a nucleotide sequence.
@@ -12,39 +13,16 @@ pub fn from_2bit_multi(
     n_bases: usize,
     dbuf: &mut Vec<u8>,
 ) -> Result<(), NucleotideError> {
-    #[cfg(all(target_arch = "aarch64", not(feature = "nosimd")))]
-    if std::arch::is_aarch64_feature_detected!("neon") {
-        return aarch64::fast_decode(ebuf, n_bases, dbuf);
-    } else {
-        // Fall back to naive implemention if SIMD feature is not enabled
+    match level() {
+        #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+        SimdLevel::Avx2 => unsafe { avx::from_2bit_multi_simd(ebuf, n_bases, dbuf) },
+        #[cfg(all(target_arch = "aarch64", not(feature = "nosimd")))]
+        SimdLevel::Neon => aarch64::fast_decode(ebuf, n_bases, dbuf),
+        // No SSE kernel
+        #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+        SimdLevel::Sse2 => naive::from_2bit_multi(ebuf, n_bases, dbuf),
+        SimdLevel::Naive => naive::from_2bit_multi(ebuf, n_bases, dbuf),
     }
-
-    #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
-    if is_x86_feature_detected!("avx2") {
-        return unsafe { avx::from_2bit_multi_simd(ebuf, n_bases, dbuf) };
-    } else {
-        // Fall back to naive implemention if SIMD feature is not enabled
-    }
-
-    // Calculate the number of chunks and the remainder
-    let n_chunks = n_bases.div_ceil(32);
-    let rem = match n_bases % 32 {
-        0 => 32, // Full chunk
-        rem => rem,
-    };
-
-    // Process all chunks except the last one
-    ebuf.iter()
-        .take(n_chunks - 1)
-        .try_for_each(|component| from_2bit(*component, 32, dbuf))?;
-
-    // Process the last one with the remainder
-    ebuf.get(n_chunks - 1)
-        .map_or(Err(NucleotideError::InvalidLength(n_bases)), |&component| {
-            from_2bit(component, rem, dbuf)
-        })?;
-
-    Ok(())
 }
 
 /// Converts a 2-bit packed representation back into a nucleotide sequence.
@@ -121,29 +99,59 @@ pub fn from_2bit(
     expected_size: usize,
     sequence: &mut Vec<u8>,
 ) -> Result<(), NucleotideError> {
-    #[cfg(all(target_arch = "aarch64", not(feature = "nosimd")))]
-    if std::arch::is_aarch64_feature_detected!("neon") {
-        unsafe { aarch64::from_2bit_simd(packed, expected_size, sequence) }
-    } else {
-        naive::from_2bit(packed, expected_size, sequence)
+    match level() {
+        #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+        SimdLevel::Avx2 => unsafe { avx::from_2bit_simd(packed, expected_size, sequence) },
+        #[cfg(all(target_arch = "aarch64", not(feature = "nosimd")))]
+        SimdLevel::Neon => unsafe { aarch64::from_2bit_simd(packed, expected_size, sequence) },
+        // No SSE kernel
+        #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+        SimdLevel::Sse2 => naive::from_2bit(packed, expected_size, sequence),
+        SimdLevel::Naive => naive::from_2bit(packed, expected_size, sequence),
     }
+}
 
-    #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
-    if is_x86_feature_detected!("avx2") {
-        unsafe { avx::from_2bit_simd(packed, expected_size, sequence) }
-    } else {
-        naive::from_2bit(packed, expected_size, sequence)
+/// Converts a 2-bit packed `u128` back into a nucleotide sequence.
+///
+/// This function reverses the packing performed by `as_2bit_u128`.
+///
+/// # Arguments
+///
+/// * `packed` - A u128 containing the 2-bit packed sequence
+/// * `expected_size` - The number of bases to unpack
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidLength` if `expected_size` is greater than 64
+/// (as a u128 can only store 64 * 2 bits).
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{as_2bit_u128, from_2bit_u128};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let seq = b"ACGTACGTACGTACGTACGTACGTACGTACGTACGTACGTACGTACGTACGTACGTACGTTTG";
+/// let packed = as_2bit_u128(seq)?;
+/// let mut unpacked = Vec::new();
+/// from_2bit_u128(packed, seq.len(), &mut unpacked)?;
+/// assert_eq!(&unpacked, seq);
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_2bit_u128(
+    packed: u128,
+    expected_size: usize,
+    sequence: &mut Vec<u8>,
+) -> Result<(), NucleotideError> {
+    if expected_size > 64 {
+        return Err(NucleotideError::InvalidLength(expected_size));
     }
-
-    // Fall back to naive implemention if:
-    // - SIMD is disabled via nosimd feature
-    // - or SIMD feature is not enabled
-    // - or required CPU features aren't availabe
-    #[cfg(any(
-        feature = "nosimd",
-        all(not(target_arch = "aarch64"), not(target_arch = "x86_64"),)
-    ))]
-    naive::from_2bit(packed, expected_size, sequence)
+    if expected_size <= 32 {
+        return from_2bit(packed as u64, expected_size, sequence);
+    }
+    from_2bit(packed as u64, 32, sequence)?;
+    from_2bit((packed >> 64) as u64, expected_size - 32, sequence)
 }
 
 /// This calls from_2bit but allocates a new Vec to store the result.
@@ -201,6 +209,22 @@ mod testing {
         }
     }
 
+    #[test]
+    fn test_from_2bit_u128() {
+        let seq = b"AGGCTTGAGGCCCATTCTCTGATCGTTTACGTGGCTTGAGGCCCATTCTCTGATCGTTTACGT";
+        let mut unpacked = Vec::new();
+        for len in 0..=seq.len() {
+            let packed = crate::as_2bit_u128(&seq[..len]).unwrap();
+            from_2bit_u128(packed, len, &mut unpacked).unwrap();
+            assert_eq!(unpacked, &seq[..len]);
+            unpacked.clear();
+        }
+        assert!(matches!(
+            from_2bit_u128(0, 65, &mut unpacked),
+            Err(NucleotideError::InvalidLength(65))
+        ));
+    }
+
     #[test]
     fn test_example_case_from_2bit() {
         let packed = 71620941647064936;
@@ -213,4 +237,25 @@ mod testing {
         assert_eq!(obs_str, exp_str);
         assert_eq!(&observed, expected);
     }
+
+    #[test]
+    fn test_from_2bit_multi_appends() {
+        let seq = b"ACGT".repeat(25);
+        let ebuf = crate::encode_alloc(&seq).unwrap();
+
+        // Every backend decodes after the bases already in the buffer
+        let mut dbuf = b"NN".to_vec();
+        from_2bit_multi(&ebuf, seq.len(), &mut dbuf).unwrap();
+        assert_eq!(&dbuf[..2], b"NN");
+        assert_eq!(&dbuf[2..], seq.as_slice());
+
+        let mut expected = b"NN".to_vec();
+        naive::from_2bit_multi(&ebuf, seq.len(), &mut expected).unwrap();
+        assert_eq!(dbuf, expected);
+
+        assert_eq!(
+            from_2bit_multi(&ebuf[..2], seq.len(), &mut dbuf),
+            Err(NucleotideError::InvalidLength(100))
+        );
+    }
 }
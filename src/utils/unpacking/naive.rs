@@ -1,16 +1,17 @@
+use crate::utils::word::PackedWord;
 use crate::NucleotideError;
 
-pub fn from_2bit(
-    packed: u64,
+pub fn from_2bit<W: PackedWord>(
+    packed: W,
     expected_size: usize,
     sequence: &mut Vec<u8>,
 ) -> Result<(), NucleotideError> {
-    if expected_size > 32 {
+    if expected_size > W::MAX_BASES {
         return Err(NucleotideError::InvalidLength(expected_size));
     }
 
     for i in 0..expected_size {
-        let bits = (packed >> (i * 2)) & 0b11;
+        let bits = (packed >> (i * 2)).low_bits();
         let base = match bits {
             0b00 => b'A',
             0b01 => b'C',
@@ -23,3 +24,29 @@ pub fn from_2bit(
 
     Ok(())
 }
+
+pub fn from_2bit_multi(
+    ebuf: &[u64],
+    n_bases: usize,
+    dbuf: &mut Vec<u8>,
+) -> Result<(), NucleotideError> {
+    // Calculate the number of chunks and the remainder
+    let n_chunks = n_bases.div_ceil(32);
+    let rem = match n_bases % 32 {
+        0 => 32, // Full chunk
+        rem => rem,
+    };
+
+    // Process all chunks except the last one
+    ebuf.iter()
+        .take(n_chunks - 1)
+        .try_for_each(|component| from_2bit(*component, 32, dbuf))?;
+
+    // Process the last one with the remainder
+    ebuf.get(n_chunks - 1)
+        .map_or(Err(NucleotideError::InvalidLength(n_bases)), |&component| {
+            from_2bit(component, rem, dbuf)
+        })?;
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use super::{naive, ByteClass};
+use std::arch::aarch64::*;
+
+/// Returns `true` if every byte of `chunk` is in `class`.
+#[inline(always)]
+unsafe fn all_match_16(chunk: uint8x16_t, class: &ByteClass) -> bool {
+    let lower = vorrq_u8(chunk, vdupq_n_u8(0x20));
+    let mut matched = vdupq_n_u8(0);
+    for &base in class.folded {
+        matched = vorrq_u8(matched, vceqq_u8(lower, vdupq_n_u8(base)));
+    }
+    for &base in class.exact {
+        matched = vorrq_u8(matched, vceqq_u8(chunk, vdupq_n_u8(base)));
+    }
+    vminvq_u8(matched) == 0xFF
+}
+
+#[inline(always)]
+pub fn first_invalid(seq: &[u8], class: &ByteClass) -> Option<usize> {
+    let simd_len = seq.len() - (seq.len() % 16);
+    unsafe {
+        for chunk_idx in (0..simd_len).step_by(16) {
+            let chunk = vld1q_u8(seq[chunk_idx..].as_ptr());
+            if !all_match_16(chunk, class) {
+                // NEON has no movemask, so the offending block is scanned for its position
+                let block = &seq[chunk_idx..chunk_idx + 16];
+                return naive::first_invalid(block, class).map(|offset| chunk_idx + offset);
+            }
+        }
+    }
+    naive::first_invalid(&seq[simd_len..], class).map(|offset| simd_len + offset)
+}
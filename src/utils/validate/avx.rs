@@ -0,0 +1,34 @@
+use super::{sse, ByteClass};
+use std::arch::x86_64::*;
+
+/// Returns a mask with a bit set for each of the 32 bytes of `chunk` in `class`.
+#[inline(always)]
+unsafe fn matches_32(chunk: __m256i, class: &ByteClass) -> u32 {
+    let lower = _mm256_or_si256(chunk, _mm256_set1_epi8(0x20));
+    let mut matched = _mm256_setzero_si256();
+    for &base in class.folded {
+        let hit = _mm256_cmpeq_epi8(lower, _mm256_set1_epi8(base as i8));
+        matched = _mm256_or_si256(matched, hit);
+    }
+    for &base in class.exact {
+        let hit = _mm256_cmpeq_epi8(chunk, _mm256_set1_epi8(base as i8));
+        matched = _mm256_or_si256(matched, hit);
+    }
+    _mm256_movemask_epi8(matched) as u32
+}
+
+#[inline(always)]
+pub fn first_invalid(seq: &[u8], class: &ByteClass) -> Option<usize> {
+    let simd_len = seq.len() - (seq.len() % 32);
+    unsafe {
+        for chunk_idx in (0..simd_len).step_by(32) {
+            let chunk = _mm256_loadu_si256(seq[chunk_idx..].as_ptr() as *const __m256i);
+            let invalid = !matches_32(chunk, class);
+            if invalid != 0 {
+                return Some(chunk_idx + invalid.trailing_zeros() as usize);
+            }
+        }
+    }
+    // The remaining bytes still fill a 128 bit register
+    sse::first_invalid(&seq[simd_len..], class).map(|offset| simd_len + offset)
+}
@@ -0,0 +1,246 @@
+use crate::utils::dispatch::{level, SimdLevel};
+
+#[cfg(all(target_arch = "aarch64", not(feature = "nosimd")))]
+pub(crate) mod aarch64;
+#[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+pub(crate) mod avx;
+pub(crate) mod naive;
+#[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+pub(crate) mod sse;
+
+/// A set of accepted input bytes.
+///
+/// The SIMD kernels fold `folded` letters by setting the case bit, so they must be
+/// lowercase ASCII letters; other accepted bytes go in `exact` and are matched as is.
+pub(crate) struct ByteClass {
+    #[cfg_attr(
+        any(
+            feature = "nosimd",
+            not(any(target_arch = "x86_64", target_arch = "aarch64"))
+        ),
+        allow(dead_code)
+    )]
+    pub(crate) folded: &'static [u8],
+    #[cfg_attr(
+        any(
+            feature = "nosimd",
+            not(any(target_arch = "x86_64", target_arch = "aarch64"))
+        ),
+        allow(dead_code)
+    )]
+    pub(crate) exact: &'static [u8],
+    /// Whether each byte is accepted, for the scalar path
+    pub(crate) lookup: [bool; 256],
+}
+
+impl ByteClass {
+    const fn new(folded: &'static [u8], exact: &'static [u8]) -> Self {
+        let mut lookup = [false; 256];
+        let mut i = 0;
+        while i < folded.len() {
+            lookup[folded[i] as usize] = true;
+            lookup[folded[i].to_ascii_uppercase() as usize] = true;
+            i += 1;
+        }
+        let mut i = 0;
+        while i < exact.len() {
+            lookup[exact[i] as usize] = true;
+            i += 1;
+        }
+        Self {
+            folded,
+            exact,
+            lookup,
+        }
+    }
+}
+
+/// A, C, G and T (case insensitive), as accepted by `encode`.
+pub(crate) static DNA: ByteClass = ByteClass::new(b"acgt", b"");
+
+/// Uppercase A, C, G and T only.
+pub(crate) static DNA_UPPERCASE: ByteClass = ByteClass::new(b"", b"ACGT");
+
+/// A, C, G and U (case insensitive).
+pub(crate) static RNA: ByteClass = ByteClass::new(b"acgu", b"");
+
+/// The IUPAC codes (case insensitive) and the gap, as accepted by `iupac::encode`.
+pub(crate) static IUPAC: ByteClass = ByteClass::new(b"acgtryswkmbdhvn", b"-");
+
+/// Returns the offset of the first byte of `seq` not in `class`.
+#[inline(always)]
+fn first_invalid(seq: &[u8], class: &ByteClass) -> Option<usize> {
+    match level() {
+        #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+        SimdLevel::Avx2 => avx::first_invalid(seq, class),
+        #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+        SimdLevel::Sse2 => sse::first_invalid(seq, class),
+        #[cfg(all(target_arch = "aarch64", not(feature = "nosimd")))]
+        SimdLevel::Neon => aarch64::first_invalid(seq, class),
+        SimdLevel::Naive => naive::first_invalid(seq, class),
+    }
+}
+
+#[inline(always)]
+fn validate(seq: &[u8], class: &ByteClass) -> Result<(), (usize, u8)> {
+    match first_invalid(seq, class) {
+        Some(position) => Err((position, seq[position])),
+        None => Ok(()),
+    }
+}
+
+/// Checks that a sequence contains only A, C, G and T (case insensitive) without encoding it.
+///
+/// This accepts exactly the sequences `encode` accepts, so it can be used to filter
+/// reads before they are packed.
+///
+/// # Errors
+///
+/// Returns the offset and value of the first invalid byte.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::is_valid_dna;
+///
+/// assert_eq!(is_valid_dna(b"ACGTacgt"), Ok(()));
+/// assert_eq!(is_valid_dna(b"ACGTNACGT"), Err((4, b'N')));
+/// ```
+pub fn is_valid_dna(seq: &[u8]) -> Result<(), (usize, u8)> {
+    validate(seq, &DNA)
+}
+
+/// Checks that a sequence contains only uppercase A, C, G and T without encoding it.
+///
+/// Lowercase bases are rejected, which is useful for input where case carries meaning,
+/// such as soft-masked repeats.
+///
+/// # Errors
+///
+/// Returns the offset and value of the first invalid byte.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::is_valid_dna_uppercase;
+///
+/// assert_eq!(is_valid_dna_uppercase(b"ACGT"), Ok(()));
+/// assert_eq!(is_valid_dna_uppercase(b"ACgT"), Err((2, b'g')));
+/// ```
+pub fn is_valid_dna_uppercase(seq: &[u8]) -> Result<(), (usize, u8)> {
+    validate(seq, &DNA_UPPERCASE)
+}
+
+/// Checks that a sequence contains only A, C, G and U (case insensitive) without encoding it.
+///
+/// `T` is rejected; note that `encode_rna` accepts it as well.
+///
+/// # Errors
+///
+/// Returns the offset and value of the first invalid byte.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::is_valid_rna;
+///
+/// assert_eq!(is_valid_rna(b"ACGUacgu"), Ok(()));
+/// assert_eq!(is_valid_rna(b"ACGT"), Err((3, b'T')));
+/// ```
+pub fn is_valid_rna(seq: &[u8]) -> Result<(), (usize, u8)> {
+    validate(seq, &RNA)
+}
+
+/// Checks that a sequence contains only IUPAC codes (case insensitive) and gaps without
+/// encoding it.
+///
+/// This accepts exactly the sequences `iupac::encode` accepts.
+///
+/// # Errors
+///
+/// Returns the offset and value of the first invalid byte.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::is_valid_iupac;
+///
+/// assert_eq!(is_valid_iupac(b"ACGTRYN-acgtryn"), Ok(()));
+/// assert_eq!(is_valid_iupac(b"ACGU"), Err((3, b'U')));
+/// ```
+pub fn is_valid_iupac(seq: &[u8]) -> Result<(), (usize, u8)> {
+    validate(seq, &IUPAC)
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::{encode_alloc, iupac};
+
+    /// Checks every backend against the lookup table for an invalid byte at each position.
+    fn check_class(class: &ByteClass, fill: u8) {
+        for len in [0, 1, 15, 16, 17, 31, 32, 33, 63, 64, 65, 150] {
+            let seq = vec![fill; len];
+            assert_eq!(naive::first_invalid(&seq, class), None);
+            assert_eq!(first_invalid(&seq, class), None);
+        }
+
+        let mut seq = vec![fill; 150];
+        for position in [0, 5, 15, 16, 31, 32, 47, 64, 100, 149] {
+            for invalid in (0..=255u8).filter(|&b| !class.lookup[b as usize]) {
+                seq[position] = invalid;
+                let expected = Some(position);
+                assert_eq!(naive::first_invalid(&seq, class), expected);
+                assert_eq!(first_invalid(&seq, class), expected);
+
+                #[cfg(all(target_arch = "x86_64", not(feature = "nosimd")))]
+                {
+                    if is_x86_feature_detected!("avx2") {
+                        assert_eq!(avx::first_invalid(&seq, class), expected);
+                    }
+                    assert_eq!(sse::first_invalid(&seq, class), expected);
+                }
+            }
+            seq[position] = fill;
+        }
+    }
+
+    #[test]
+    fn test_backends_agree() {
+        check_class(&DNA, b'g');
+        check_class(&DNA_UPPERCASE, b'G');
+        check_class(&RNA, b'U');
+        check_class(&IUPAC, b'n');
+    }
+
+    #[test]
+    fn test_matches_encoders() {
+        for byte in 0..=255u8 {
+            let seq = [b'A', byte, b'C'];
+            assert_eq!(is_valid_dna(&seq).is_ok(), encode_alloc(&seq).is_ok());
+            assert_eq!(
+                is_valid_iupac(&seq).is_ok(),
+                iupac::encode_alloc(&seq).is_ok()
+            );
+            assert_eq!(
+                is_valid_dna_uppercase(&seq).is_ok(),
+                matches!(byte, b'A' | b'C' | b'G' | b'T')
+            );
+            assert_eq!(
+                is_valid_rna(&seq).is_ok(),
+                matches!(byte, b'A' | b'C' | b'G' | b'U' | b'a' | b'c' | b'g' | b'u')
+            );
+        }
+    }
+
+    #[test]
+    fn test_reports_first_invalid() {
+        let mut seq = b"ACGT".repeat(40);
+        seq[70] = b'-';
+        seq[90] = b'N';
+        assert_eq!(is_valid_dna(&seq), Err((70, b'-')));
+        assert_eq!(is_valid_iupac(&seq), Ok(()));
+        assert_eq!(is_valid_rna(&seq), Err((3, b'T')));
+        assert_eq!(is_valid_dna(b""), Ok(()));
+    }
+}
@@ -0,0 +1,6 @@
+use super::ByteClass;
+
+#[inline(always)]
+pub fn first_invalid(seq: &[u8], class: &ByteClass) -> Option<usize> {
+    seq.iter().position(|&b| !class.lookup[b as usize])
+}
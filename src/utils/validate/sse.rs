@@ -0,0 +1,31 @@
+use super::{naive, ByteClass};
+use std::arch::x86_64::*;
+
+/// Returns a mask with a bit set for each of the 16 bytes of `chunk` in `class`.
+#[inline(always)]
+unsafe fn matches_16(chunk: __m128i, class: &ByteClass) -> i32 {
+    let lower = _mm_or_si128(chunk, _mm_set1_epi8(0x20));
+    let mut matched = _mm_setzero_si128();
+    for &base in class.folded {
+        matched = _mm_or_si128(matched, _mm_cmpeq_epi8(lower, _mm_set1_epi8(base as i8)));
+    }
+    for &base in class.exact {
+        matched = _mm_or_si128(matched, _mm_cmpeq_epi8(chunk, _mm_set1_epi8(base as i8)));
+    }
+    _mm_movemask_epi8(matched)
+}
+
+#[inline(always)]
+pub fn first_invalid(seq: &[u8], class: &ByteClass) -> Option<usize> {
+    let simd_len = seq.len() - (seq.len() % 16);
+    unsafe {
+        for chunk_idx in (0..simd_len).step_by(16) {
+            let chunk = _mm_loadu_si128(seq[chunk_idx..].as_ptr() as *const __m128i);
+            let invalid = !matches_16(chunk, class) & 0xFFFF;
+            if invalid != 0 {
+                return Some(chunk_idx + invalid.trailing_zeros() as usize);
+            }
+        }
+    }
+    naive::first_invalid(&seq[simd_len..], class).map(|offset| simd_len + offset)
+}
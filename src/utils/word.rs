@@ -0,0 +1,306 @@
+//! Packing functions generic over the width of the packed word.
+//!
+//! The crate level functions (`as_2bit`, `from_2bit`, `hdist_scalar`, ...) work on `u64`
+//! and are SIMD accelerated where possible. The functions in this module accept any
+//! [`PackedWord`] so that short sequences such as barcodes can be stored in a smaller
+//! integer, and long k-mers in a `u128`.
+//!
+//! The layout is the same for every width: bases are packed from least significant to
+//! most significant bits, so a word holds `BITS / 2` bases and the first bases of a wider
+//! word match the narrower one.
+//!
+//! ```rust
+//! use bitnuc::word::{as_2bit, from_2bit, hdist_scalar};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! // 8 bases fit in a u16
+//! let packed = as_2bit::<u16>(b"ACGTACGT")?;
+//! assert_eq!(packed, 0b1110010011100100);
+//! assert_eq!(as_2bit::<u32>(b"ACGTACGT")?, packed as u32);
+//!
+//! let mut unpacked = Vec::new();
+//! from_2bit(packed, 8, &mut unpacked)?;
+//! assert_eq!(&unpacked, b"ACGTACGT");
+//!
+//! let other = as_2bit::<u16>(b"ACGTTCGT")?;
+//! assert_eq!(hdist_scalar(packed, other, 8)?, 1);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::{BitAnd, BitOr, BitXor, Not, Shl, Shr};
+
+use crate::utils::functions::{hamming, revcomp};
+use crate::utils::{packing, unpacking};
+use crate::NucleotideError;
+
+/// An unsigned integer that can hold a 2-bit packed nucleotide sequence.
+///
+/// Implemented for `u8`, `u16`, `u32`, `u64` and `u128`.
+pub trait PackedWord:
+    Copy
+    + Default
+    + Debug
+    + Eq
+    + Ord
+    + Hash
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+    + Shl<usize, Output = Self>
+    + Shr<usize, Output = Self>
+{
+    /// The number of bits in the word
+    const BITS: usize;
+    /// The maximum number of bases the word can hold
+    const MAX_BASES: usize = Self::BITS / 2;
+    /// The word with no bits set
+    const ZERO: Self;
+    /// The lower bit of every 2-bit group (0b0101...)
+    const LOWER_BITS: Self;
+
+    /// Creates a word from a 2-bit nucleotide code.
+    fn from_bits(bits: u8) -> Self;
+
+    /// Returns the 2-bit nucleotide code in the lowest bits of the word.
+    fn low_bits(self) -> u8;
+
+    /// Returns the number of set bits in the word.
+    fn count_ones(self) -> u32;
+
+    /// Returns a mask covering the lowest `n_bases` bases of the word.
+    fn mask(n_bases: usize) -> Self;
+
+    /// Reverses the order of the 2-bit groups within the word.
+    fn reverse_2bit_groups(self) -> Self;
+}
+
+macro_rules! impl_packed_word {
+    ($($t:ty),*) => {
+        $(
+            impl PackedWord for $t {
+                const BITS: usize = <$t>::BITS as usize;
+                const ZERO: Self = 0;
+                const LOWER_BITS: Self = <$t>::MAX / 3;
+
+                #[inline(always)]
+                fn from_bits(bits: u8) -> Self {
+                    bits as $t
+                }
+
+                #[inline(always)]
+                fn low_bits(self) -> u8 {
+                    (self & 0b11) as u8
+                }
+
+                #[inline(always)]
+                fn count_ones(self) -> u32 {
+                    <$t>::count_ones(self)
+                }
+
+                #[inline(always)]
+                fn mask(n_bases: usize) -> Self {
+                    if n_bases >= Self::MAX_BASES {
+                        <$t>::MAX
+                    } else {
+                        (1 << (n_bases * 2)) - 1
+                    }
+                }
+
+                #[inline(always)]
+                fn reverse_2bit_groups(self) -> Self {
+                    // Masks selecting alternating 2-bit and 4-bit groups
+                    const PAIR_MASK: $t = <$t>::MAX / 0xFF * 0x33;
+                    const NIBBLE_MASK: $t = <$t>::MAX / 0xFF * 0x0F;
+
+                    // Swap adjacent 2-bit groups within each nibble
+                    let x = ((self >> 2) & PAIR_MASK) | ((self & PAIR_MASK) << 2);
+                    // Swap adjacent nibbles within each byte
+                    let x = ((x >> 4) & NIBBLE_MASK) | ((x & NIBBLE_MASK) << 4);
+                    // Reverse the byte order
+                    x.swap_bytes()
+                }
+            }
+        )*
+    };
+}
+
+impl_packed_word!(u8, u16, u32, u64, u128);
+
+/// Converts a nucleotide sequence into a 2-bit packed word.
+///
+/// Generic version of [`as_2bit`](crate::as_2bit).
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidBase` if the sequence contains any characters
+/// other than A,C,G,T (case insensitive).
+///
+/// Returns `NucleotideError::SequenceTooLong` if the input sequence is longer
+/// than `W::MAX_BASES`.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{word::as_2bit, NucleotideError};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// assert_eq!(as_2bit::<u8>(b"ACGT")?, 0b11100100);
+/// assert!(matches!(
+///     as_2bit::<u8>(b"ACGTA"),
+///     Err(NucleotideError::SequenceTooLong(5))
+/// ));
+/// # Ok(())
+/// # }
+/// ```
+#[inline(always)]
+pub fn as_2bit<W: PackedWord>(seq: &[u8]) -> Result<W, NucleotideError> {
+    packing::naive::as_2bit(seq)
+}
+
+/// Converts a 2-bit packed word back into a nucleotide sequence.
+///
+/// Generic version of [`from_2bit`](crate::from_2bit).
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidLength` if `expected_size` is greater than
+/// `W::MAX_BASES`.
+#[inline(always)]
+pub fn from_2bit<W: PackedWord>(
+    packed: W,
+    expected_size: usize,
+    sequence: &mut Vec<u8>,
+) -> Result<(), NucleotideError> {
+    unpacking::naive::from_2bit(packed, expected_size, sequence)
+}
+
+/// Calculates the hamming distance between two 2-bit packed words.
+///
+/// Generic version of [`hdist_scalar`](crate::hdist_scalar).
+///
+/// # Errors
+///
+/// Returns `NucleotideError::InvalidLength` if `len` is greater than `W::MAX_BASES`.
+#[inline(always)]
+pub fn hdist_scalar<W: PackedWord>(u: W, v: W, len: usize) -> Result<u32, NucleotideError> {
+    hamming::scalar::hdist_word(u, v, len)
+}
+
+/// Calculates the reverse complement of a 2-bit packed word.
+///
+/// Generic version of [`revcomp_2bit`](crate::revcomp_2bit).
+///
+/// `len` must be <= `W::MAX_BASES`.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::word::{as_2bit, revcomp_2bit};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let packed = as_2bit::<u16>(b"AACGT")?;
+/// assert_eq!(revcomp_2bit(packed, 5), as_2bit::<u16>(b"ACGTT")?);
+/// # Ok(())
+/// # }
+/// ```
+#[inline(always)]
+pub fn revcomp_2bit<W: PackedWord>(packed: W, len: usize) -> W {
+    revcomp::naive::revcomp_word(packed, len)
+}
+
+/// Calculates the canonical form of a 2-bit packed k-mer.
+///
+/// Generic version of [`canonical_2bit`](crate::canonical_2bit).
+///
+/// `k` must be <= `W::MAX_BASES`.
+#[inline(always)]
+pub fn canonical_2bit<W: PackedWord>(kmer: W, k: usize) -> W {
+    kmer.min(revcomp_2bit(kmer, k))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_width<W: PackedWord + Into<u128>>() {
+        let seq = b"ACTGGAAAATTTTAAGGCCCGTAGCATGATCGATCGTAGCTAGTCGAGGCTTGAGGCCCATTC";
+        let mut unpacked = Vec::new();
+
+        for len in 0..=W::MAX_BASES {
+            let packed: W = as_2bit(&seq[..len]).unwrap();
+
+            // Matches the u128 layout
+            assert_eq!(packed.into(), crate::as_2bit_u128(&seq[..len]).unwrap());
+
+            from_2bit(packed, len, &mut unpacked).unwrap();
+            assert_eq!(unpacked, &seq[..len]);
+            unpacked.clear();
+
+            let rc = revcomp_2bit(packed, len);
+            assert_eq!(
+                rc.into(),
+                crate::revcomp_2bit_u128(packed.into(), len),
+                "Failed for {} bases in {} bits",
+                len,
+                W::BITS
+            );
+            assert_eq!(hdist_scalar(packed, packed, len), Ok(0));
+        }
+
+        assert!(matches!(
+            as_2bit::<W>(&seq[..W::MAX_BASES + 1]),
+            Err(NucleotideError::SequenceTooLong(n)) if n == W::MAX_BASES + 1
+        ));
+        assert!(matches!(
+            from_2bit(W::ZERO, W::MAX_BASES + 1, &mut unpacked),
+            Err(NucleotideError::InvalidLength(n)) if n == W::MAX_BASES + 1
+        ));
+        assert!(hdist_scalar(W::ZERO, W::ZERO, W::MAX_BASES + 1).is_err());
+    }
+
+    #[test]
+    fn test_all_widths() {
+        check_width::<u8>();
+        check_width::<u16>();
+        check_width::<u32>();
+        check_width::<u64>();
+    }
+
+    #[test]
+    fn test_u128_limits() {
+        let seq = vec![b'A'; 65];
+        assert!(matches!(
+            as_2bit::<u128>(&seq),
+            Err(NucleotideError::SequenceTooLong(65))
+        ));
+        assert_eq!(
+            as_2bit::<u128>(&seq[..64]).unwrap(),
+            crate::as_2bit_u128(&seq[..64]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_hdist_scalar_widths() {
+        let u = as_2bit::<u32>(b"ACGTACGTACGTACGT").unwrap();
+        let v = as_2bit::<u32>(b"ACGTACGAACGTACGA").unwrap();
+        assert_eq!(hdist_scalar(u, v, 16), Ok(2));
+        assert_eq!(hdist_scalar(u, v, 15), Ok(1));
+
+        let u = as_2bit::<u8>(b"ACGT").unwrap();
+        let v = as_2bit::<u8>(b"TGCA").unwrap();
+        assert_eq!(hdist_scalar(u, v, 4), Ok(4));
+    }
+
+    #[test]
+    fn test_mask() {
+        assert_eq!(u8::mask(0), 0);
+        assert_eq!(u8::mask(3), 0b111111);
+        assert_eq!(u8::mask(4), u8::MAX);
+        assert_eq!(u64::mask(32), u64::MAX);
+        assert_eq!(u128::mask(33), (1u128 << 66) - 1);
+    }
+}
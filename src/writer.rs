@@ -0,0 +1,247 @@
+use std::io;
+
+use crate::{as_2bit, NucleotideError, PackedSequence};
+
+#[inline(always)]
+fn is_newline(base: &u8) -> bool {
+    *base == b'\n' || *base == b'\r'
+}
+
+/// Packs ASCII nucleotides into 2-bit words as they are written.
+///
+/// Input may be split into chunks of any size; the words produced are identical to
+/// `encode` of the concatenated input, so a sequence can be packed while it streams
+/// off disk without holding the ASCII in memory.
+///
+/// # Examples
+///
+/// ```rust
+/// use bitnuc::{encode_alloc, PackedSequence, PackedWriter};
+/// use std::io::Write;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut writer = PackedWriter::new().skip_newlines(true);
+/// writer.write_all(b"ACGTACGTAC\nGTACGTACGTACGT\r\n")?;
+/// writer.write_all(b"ACGTACGTACGTTTGCA\n")?;
+/// assert_eq!(writer.len(), 41);
+///
+/// let seq = PackedSequence::from(writer);
+/// assert_eq!(seq.to_vec()?, b"ACGTACGTACGTACGTACGTACGTACGTACGTACGTTTGCA");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct PackedWriter {
+    /// The completed 32-base words
+    data: Vec<u64>,
+    /// The bases packed so far into the next word
+    pending: u64,
+    /// The number of bases in `pending`
+    pending_len: usize,
+    /// The total number of bases written
+    length: usize,
+    /// Whether `\n` and `\r` are dropped from the input
+    skip_newlines: bool,
+}
+
+impl PackedWriter {
+    /// Creates an empty writer that accepts only A, C, G and T (case insensitive).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether `\n` and `\r` bytes are skipped instead of rejected, so that
+    /// line-wrapped FASTA sequence can be written directly.
+    pub fn skip_newlines(mut self, skip: bool) -> Self {
+        self.skip_newlines = skip;
+        self
+    }
+
+    /// Returns the number of bases written.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns true if no bases have been written.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Returns the packed words and the number of bases written.
+    ///
+    /// The words are identical to those produced by `encode` for the same bases.
+    pub fn finish(mut self) -> (Vec<u64>, usize) {
+        if self.pending_len > 0 {
+            self.data.push(self.pending);
+        }
+        (self.data, self.length)
+    }
+
+    #[inline(always)]
+    fn push_bits(&mut self, bits: u64, n_bases: usize) {
+        self.pending |= bits << (2 * self.pending_len);
+        self.pending_len += n_bases;
+        self.length += n_bases;
+        if self.pending_len == 32 {
+            self.data.push(self.pending);
+            self.pending = 0;
+            self.pending_len = 0;
+        }
+    }
+}
+
+impl io::Write for PackedWriter {
+    /// Packs the bases of `buf`.
+    ///
+    /// If `buf` contains an invalid base, the bytes before it are still consumed and
+    /// their count is returned; an error of kind `InvalidData` is returned once the
+    /// invalid base is at the start of the input. It wraps a
+    /// `NucleotideError::InvalidBase` whose position is the number of bases written
+    /// before the invalid one.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut consumed = 0;
+        while consumed < buf.len() {
+            let rest = &buf[consumed..];
+            if self.skip_newlines && is_newline(&rest[0]) {
+                consumed += 1;
+                continue;
+            }
+
+            // Fill at most the remainder of the pending word
+            let mut take = rest.len().min(32 - self.pending_len);
+            if self.skip_newlines {
+                if let Some(end) = rest[..take].iter().position(is_newline) {
+                    take = end;
+                }
+            }
+
+            match as_2bit(&rest[..take]) {
+                Ok(bits) => self.push_bits(bits, take),
+                Err(err) => {
+                    // Pack the bases preceding the invalid one
+                    let valid = match err {
+                        NucleotideError::InvalidBase { position, .. } => position,
+                        _ => 0,
+                    };
+                    if valid > 0 {
+                        self.push_bits(as_2bit(&rest[..valid])?, valid);
+                    }
+                    consumed += valid;
+                    if consumed == 0 {
+                        // Report the offset within the whole stream
+                        return Err(err.offset_position(self.length).into());
+                    }
+                    return Ok(consumed);
+                }
+            }
+            consumed += take;
+        }
+        Ok(consumed)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl From<PackedWriter> for PackedSequence {
+    fn from(writer: PackedWriter) -> Self {
+        let (data, length) = writer.finish();
+        PackedSequence::from_parts(data, length, Vec::new(), Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encode_alloc;
+    use nucgen::Sequence;
+    use std::io::Write;
+
+    #[test]
+    fn test_writer_matches_encode() {
+        let mut rng = rand::thread_rng();
+        let mut seq = Sequence::new();
+
+        for len in [1, 31, 32, 33, 100, 1000] {
+            seq.fill_buffer(&mut rng, len);
+            let expected = encode_alloc(seq.bytes()).unwrap();
+
+            for chunk_size in [1, 3, 7, 32, 33, 64, 1000] {
+                let mut writer = PackedWriter::new();
+                for chunk in seq.bytes().chunks(chunk_size) {
+                    writer.write_all(chunk).unwrap();
+                }
+                assert_eq!(writer.len(), len);
+                assert_eq!(writer.finish(), (expected.clone(), len));
+            }
+        }
+    }
+
+    #[test]
+    fn test_writer_skip_newlines() {
+        let mut rng = rand::thread_rng();
+        let mut seq = Sequence::new();
+        seq.fill_buffer(&mut rng, 500);
+
+        let mut wrapped = Vec::new();
+        for line in seq.bytes().chunks(60) {
+            wrapped.extend_from_slice(line);
+            wrapped.extend_from_slice(b"\r\n");
+        }
+
+        let mut writer = PackedWriter::new().skip_newlines(true);
+        for chunk in wrapped.chunks(45) {
+            writer.write_all(chunk).unwrap();
+        }
+        let packed = PackedSequence::from(writer);
+        assert_eq!(packed, PackedSequence::new(seq.bytes()).unwrap());
+
+        // Newlines are invalid bases by default
+        let mut writer = PackedWriter::new();
+        assert!(writer.write_all(b"ACGT\nACGT").is_err());
+    }
+
+    #[test]
+    fn test_writer_invalid_base() {
+        let mut writer = PackedWriter::new();
+        assert_eq!(writer.write(b"ACGTN").unwrap(), 4);
+        let err = writer.write(b"N").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(writer.len(), 4);
+        assert_eq!(
+            err.get_ref().and_then(|err| err.downcast_ref()),
+            Some(&NucleotideError::InvalidBase {
+                base: b'N',
+                position: 4
+            })
+        );
+
+        writer.write_all(&[b'A'; 26]).unwrap();
+        assert_eq!(writer.write(b"CCGNGG").unwrap(), 3);
+        let err = writer.write_all(b"NGG").unwrap_err();
+        assert_eq!(writer.len(), 33);
+        assert_eq!(
+            err.get_ref().and_then(|err| err.downcast_ref()),
+            Some(&NucleotideError::InvalidBase {
+                base: b'N',
+                position: 33
+            })
+        );
+
+        let mut expected = b"ACGT".to_vec();
+        expected.extend_from_slice(&[b'A'; 26]);
+        expected.extend_from_slice(b"CCG");
+        assert_eq!(writer.finish().0, encode_alloc(&expected).unwrap());
+    }
+
+    #[test]
+    fn test_writer_empty() {
+        let writer = PackedWriter::new();
+        assert!(writer.is_empty());
+        assert_eq!(
+            PackedSequence::from(writer),
+            PackedSequence::new(b"").unwrap()
+        );
+    }
+}